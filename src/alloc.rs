@@ -0,0 +1,175 @@
+//! A global allocator which counts allocations and tracks the peak heap size.
+//!
+//! The library doesn't install it, since a crate can only have one global allocator.
+//! A binary opts in with
+//!
+//! ```ignore
+//! #[global_allocator]
+//! static GLOBAL: aoc2020::alloc::CountingAlloc = aoc2020::alloc::CountingAlloc;
+//! ```
+//!
+//! and without that, `measure` reports nothing allocated.
+//!
+//! It passes everything through to the system allocator, and keeps its counts in
+//! relaxed atomics, so it costs a few nanoseconds per allocation. The counts are
+//! for the whole process, so `measure` only gives clean numbers when nothing else is
+//! allocating at the same time.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fmt;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+pub struct CountingAlloc;
+
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+static ALLOCATED: AtomicU64 = AtomicU64::new(0);
+static LIVE: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+fn grow(size: usize) {
+    let live = LIVE.fetch_add(size, Ordering::Relaxed) + size;
+    PEAK.fetch_max(live, Ordering::Relaxed);
+}
+
+fn record(size: usize) {
+    ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+    ALLOCATED.fetch_add(size as u64, Ordering::Relaxed);
+    grow(size);
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            record(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            record(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        LIVE.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+
+    /// A realloc counts as one allocation of the new size, since growing a Vec is
+    /// usually what we're looking for.
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+            ALLOCATED.fetch_add(new_size as u64, Ordering::Relaxed);
+            if new_size > layout.size() {
+                grow(new_size - layout.size());
+            } else {
+                LIVE.fetch_sub(layout.size() - new_size, Ordering::Relaxed);
+            }
+        }
+        new_ptr
+    }
+}
+
+// The unit tests in this crate count allocations too.
+#[cfg(test)]
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+/// What some code allocated.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct AllocStats {
+    /// The number of allocations and reallocations.
+    pub allocations: u64,
+    /// The total bytes asked for, including anything freed since.
+    pub allocated: u64,
+    /// The most heap in use at once, over what was in use at the start.
+    pub peak: usize,
+}
+
+impl fmt::Display for AllocStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "peak {}, {} allocation{}",
+            format_bytes(self.peak as u64),
+            self.allocations,
+            if self.allocations == 1 { "" } else { "s" }
+        )
+    }
+}
+
+/// The bytes of heap in use right now.
+pub fn live() -> usize {
+    LIVE.load(Ordering::Relaxed)
+}
+
+/// Run `f` and count what it allocates. This resets the peak, so calls can't nest.
+pub fn measure<T, F: FnOnce() -> T>(f: F) -> (T, AllocStats) {
+    let start = live();
+    PEAK.store(start, Ordering::Relaxed);
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let allocated = ALLOCATED.load(Ordering::Relaxed);
+
+    let result = f();
+    let stats = AllocStats {
+        allocations: ALLOCATIONS.load(Ordering::Relaxed) - allocations,
+        allocated: ALLOCATED.load(Ordering::Relaxed) - allocated,
+        peak: PEAK.load(Ordering::Relaxed).saturating_sub(start),
+    };
+    (result, stats)
+}
+
+/// A size like `512 B`, `1.5 KiB` or `114.4 MiB`.
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+
+    #[test]
+    fn test_measure() {
+        // Other tests allocate at the same time, so these are only lower bounds.
+        let (v, stats) = measure(|| vec![0u64; 100_000]);
+        assert!(stats.allocations >= 1);
+        assert!(stats.allocated >= 800_000);
+        assert!(stats.peak >= 800_000);
+        drop(v);
+
+        let (_, stats) = measure(|| {
+            let mut v = vec![];
+            for i in 0..1000 {
+                v.push(i);
+            }
+        });
+        assert!(stats.allocations >= 2, "Growing a Vec reallocates: {:?}", stats);
+    }
+
+    #[test]
+    fn test_format() {
+        assert_eq!("0 B", format_bytes(0));
+        assert_eq!("1023 B", format_bytes(1023));
+        assert_eq!("1.5 KiB", format_bytes(1536));
+        assert_eq!("114.4 MiB", format_bytes(120_000_000));
+        let stats = AllocStats { allocations: 1, allocated: 8, peak: 8 };
+        assert_eq!("peak 8 B, 1 allocation", stats.to_string());
+    }
+}
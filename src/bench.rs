@@ -0,0 +1,178 @@
+//! Repeatable timings for each phase of a solution.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use crate::alloc::{self, AllocStats};
+use crate::error::ParseError;
+use crate::solution::Solution;
+use crate::util::{json_string, Source};
+
+/// How many times to run each phase.
+#[derive(Debug, Clone, Copy)]
+pub struct BenchOptions {
+    /// Untimed runs before the timed ones, to warm up caches and the allocator.
+    pub warmup: usize,
+    pub iterations: usize,
+}
+
+impl Default for BenchOptions {
+    fn default() -> BenchOptions {
+        BenchOptions {
+            warmup: 1,
+            iterations: 10,
+        }
+    }
+}
+
+/// Summary statistics for a set of timings.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub p95: Duration,
+}
+
+impl Stats {
+    /// Summarize some timings. There must be at least one.
+    pub fn from_samples(samples: &[Duration]) -> Stats {
+        let mut sorted = samples.to_vec();
+        sorted.sort_unstable();
+        // Nearest-rank percentiles.
+        let rank = |p: f64| sorted[((p * sorted.len() as f64).ceil() as usize).max(1) - 1];
+        Stats {
+            min: sorted[0],
+            median: rank(0.5),
+            p95: rank(0.95),
+        }
+    }
+}
+
+/// Time `f`, running it `warmup` times first.
+pub fn measure<T, F: FnMut() -> T>(opts: &BenchOptions, mut f: F) -> Stats {
+    for _ in 0..opts.warmup {
+        black_box(f());
+    }
+    let samples: Vec<Duration> = (0..opts.iterations.max(1))
+        .map(|_| {
+            let now = Instant::now();
+            black_box(f());
+            now.elapsed()
+        })
+        .collect();
+    Stats::from_samples(&samples)
+}
+
+/// Timings for one day's solution on one input.
+#[derive(Debug, Clone)]
+pub struct DayBench {
+    pub day: u32,
+    pub input: String,
+    /// "parse", "part1" and/or "part2", with what one run of each allocates.
+    /// Unimplemented parts are left out.
+    pub phases: Vec<(String, Stats, AllocStats)>,
+}
+
+fn ms(d: Duration) -> f64 {
+    d.as_secs_f64() * 1000.0
+}
+
+impl DayBench {
+    /// This benchmark as a single line of JSON.
+    pub fn to_json(&self, opts: &BenchOptions) -> String {
+        let phases: Vec<String> = self
+            .phases
+            .iter()
+            .map(|(name, s, a)| {
+                format!(
+                    "{}: {{\"min_ms\": {:.6}, \"median_ms\": {:.6}, \"p95_ms\": {:.6}, \"allocations\": {}, \"peak_bytes\": {}}}",
+                    json_string(name),
+                    ms(s.min),
+                    ms(s.median),
+                    ms(s.p95),
+                    a.allocations,
+                    a.peak
+                )
+            })
+            .collect();
+        format!(
+            "{{\"day\": {}, \"input\": {}, \"warmup\": {}, \"iterations\": {}, \"phases\": {{{}}}}}",
+            self.day,
+            json_string(&self.input),
+            opts.warmup,
+            opts.iterations,
+            phases.join(", ")
+        )
+    }
+}
+
+type PartFn<I> = fn(&I) -> Option<String>;
+
+/// Time parsing and each implemented part of a solution separately. Reading the input
+/// isn't included. Allocations are counted on an untimed run, so that the counting
+/// doesn't skew the timings.
+pub fn bench<S: Solution>(source: &Source, args: &[String], opts: &BenchOptions) -> Result<DayBench, ParseError> {
+    let (input, parse_alloc) = alloc::measure(|| S::parse(source, args));
+    let input = input?;
+    let mut phases = vec![(String::from("parse"), measure(opts, || S::parse(source, args)), parse_alloc)];
+
+    let parts: [(&str, PartFn<S::Input>); 2] = [("part1", S::part1), ("part2", S::part2)];
+    for &(name, solve) in parts.iter() {
+        // The first run tells us whether the part is implemented, and doubles as a warm-up.
+        let (answer, part_alloc) = alloc::measure(|| solve(&input));
+        if answer.is_none() {
+            continue;
+        }
+        let opts = BenchOptions {
+            warmup: opts.warmup.saturating_sub(1),
+            ..*opts
+        };
+        phases.push((String::from(name), measure(&opts, || solve(&input)), part_alloc));
+    }
+
+    Ok(DayBench {
+        day: S::DAY,
+        input: source.name.clone(),
+        phases,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+
+    #[test]
+    fn test_stats() {
+        let ms = |n| Duration::from_millis(n);
+        let samples: Vec<Duration> = (1..=20).rev().map(ms).collect();
+        let stats = Stats::from_samples(&samples);
+        assert_eq!(stats, Stats { min: ms(1), median: ms(10), p95: ms(19) });
+
+        let stats = Stats::from_samples(&[ms(3)]);
+        assert_eq!(stats, Stats { min: ms(3), median: ms(3), p95: ms(3) });
+    }
+
+    #[test]
+    fn test_to_json() {
+        let b = DayBench {
+            day: 7,
+            input: String::from("inputs/day7.txt"),
+            phases: vec![(
+                String::from("parse"),
+                Stats::from_samples(&[Duration::from_micros(1500)]),
+                AllocStats {
+                    allocations: 12,
+                    allocated: 4096,
+                    peak: 2048,
+                },
+            )],
+        };
+        assert_eq!(
+            b.to_json(&BenchOptions::default()),
+            "{\"day\": 7, \"input\": \"inputs/day7.txt\", \"warmup\": 1, \"iterations\": 10, \
+             \"phases\": {\"parse\": {\"min_ms\": 1.500000, \"median_ms\": 1.500000, \"p95_ms\": 1.500000, \
+             \"allocations\": 12, \"peak_bytes\": 2048}}}"
+        );
+    }
+}
@@ -0,0 +1,671 @@
+use std::env;
+use std::fs;
+use std::panic;
+use std::path::Path;
+use std::process;
+use std::time::{Duration, Instant};
+
+use aoc2020::alloc::{AllocStats, CountingAlloc};
+use aoc2020::bench::{BenchOptions, DayBench};
+use aoc2020::error::ParseError;
+use aoc2020::fuzz;
+use aoc2020::log;
+use aoc2020::manifest::{read_manifest, Expected};
+use aoc2020::pool;
+use aoc2020::solution::{days, find_day, format_duration, Day, Run};
+use aoc2020::util::{json_string, Source};
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+const USAGE: &str = "Usage:
+    aoc run <day> [--part N] [--format text|json] [--mem] [--jobs N] [input] [args...]
+    aoc run <day> [--part N] [--format text|json] [--mem] [--jobs N] --input-string TEXT [args...]
+    aoc run all [--part N] [--format text|json] [--mem] [--jobs N]
+    aoc verify [--day N] [manifest]
+    aoc bench [--day N] [--warmup W] [--iterations N] [--json PATH] [--size N [--seed S]]
+    aoc fuzz [--target NAME|DAY] [--cases N] [--seed S] [--corpus DIR]
+    aoc generate <day> [--seed S] [--size N] [--output PATH] [--check]
+
+The input defaults to inputs/dayN.txt, and `-` reads it from stdin. Any args after the input
+are passed to the solution, e.g. `aoc run 9 inputs/day9.sample.txt 5` for a preamble of 5.
+With --input-string the puzzle input is given inline and every positional arg after the day
+is passed to the solution, as it is when the first one is a flag like day 1's `--k 4`.
+
+`--format json` prints one JSON object per part, {day, part, answer, elapsed_ms, allocations,
+peak_bytes}, then a {day, note} for each line a solution shows besides its answers, like day
+3's trees on each slope, and sends everything else to stderr. `--mem` adds the peak heap use
+and number of allocations of each phase to the text output.
+
+`--jobs N` runs on up to N threads: `run all` solves the days in parallel and prints them in
+order, and the searches in days 1, 8 and 20 try their candidates in parallel. The answers are
+the same either way, but the `--mem` counts are for the whole process, so with more than one
+job they include whatever else was running at the time.
+
+`-v` anywhere on the command line logs a summary of each part to stderr, and `-vv` also
+traces every step. Both are off by default.
+
+`verify` checks every solution against the expected answers in inputs/answers.toml.
+
+`bench` times parsing and each part separately, and counts what one run of each allocates,
+using inputs/dayN.sample.txt when there's no real input, or a generated input of about N
+records with `--size`. `--json` writes the results as one JSON object per line, for diffing
+across commits.
+
+`fuzz` feeds mutated lines of inputs/dayN*.txt to the input parsers and reports any that
+panic, with the seed to replay it. Each target runs 10,000 cases by default.
+
+`generate` makes a random input of about N records (default 100) and prints it, along with
+any answers it knows by construction. `--check` runs the solution on it and compares.";
+
+const MANIFEST: &str = "inputs/answers.toml";
+
+fn usage_error(msg: &str) -> ! {
+    eprintln!("{}\n\n{}", msg, USAGE);
+    process::exit(2);
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Format {
+    Text,
+    Json,
+}
+
+impl Format {
+    /// Print a line of human-readable output. In JSON mode it goes to stderr, to keep
+    /// stdout machine-readable.
+    fn say(self, msg: &str) {
+        match self {
+            Format::Text => println!("{}", msg),
+            Format::Json => eprintln!("{}", msg),
+        }
+    }
+}
+
+struct RunArgs {
+    day: Option<u32>,
+    format: Format,
+    parts: Vec<u32>,
+    input: Option<String>,
+    input_string: Option<String>,
+    extra: Vec<String>,
+    mem: bool,
+    jobs: usize,
+}
+
+fn parse_run_args(args: &[String]) -> RunArgs {
+    let mut parts = vec![1, 2];
+    let mut input_string = None;
+    let mut format = Format::Text;
+    let mut mem = false;
+    let mut jobs = 1;
+    let mut positional = vec![];
+    let mut i = 0;
+    while i < args.len() {
+        if args[i] == "--part" {
+            let part = args.get(i + 1).and_then(|p| p.parse::<u32>().ok());
+            match part {
+                Some(p) if p == 1 || p == 2 => parts = vec![p],
+                _ => usage_error("--part must be 1 or 2"),
+            }
+            i += 2;
+        } else if args[i] == "--format" {
+            format = match args.get(i + 1).map(|f| f.as_str()) {
+                Some("text") => Format::Text,
+                Some("json") => Format::Json,
+                _ => usage_error("--format must be text or json"),
+            };
+            i += 2;
+        } else if args[i] == "--mem" {
+            mem = true;
+            i += 1;
+        } else if args[i] == "--jobs" {
+            match args.get(i + 1).and_then(|n| n.parse::<usize>().ok()) {
+                Some(n) if n > 0 => jobs = n,
+                _ => usage_error("--jobs must be a positive number"),
+            }
+            i += 2;
+        } else if args[i] == "--input-string" {
+            match args.get(i + 1) {
+                Some(text) => input_string = Some(text.clone()),
+                None => usage_error("--input-string needs the text of the input"),
+            }
+            i += 2;
+        } else {
+            positional.push(args[i].clone());
+            i += 1;
+        }
+    }
+
+    if positional.is_empty() {
+        usage_error("Expected a day or 'all'");
+    }
+    let day = if positional[0] == "all" {
+        None
+    } else {
+        match positional[0].parse::<u32>() {
+            Ok(d) => Some(d),
+            Err(_) => usage_error(&format!("Invalid day: {}", positional[0])),
+        }
+    };
+
+    // With an inline input, everything after the day is an arg for the solution, and
+    // so is everything after it if it's a flag for the solution rather than a path.
+    let has_input = input_string.is_none() && positional.get(1).is_some_and(|p| !p.starts_with("--"));
+    let first_extra = if has_input { 2 } else { 1 };
+    RunArgs {
+        day,
+        format,
+        parts,
+        input: positional.get(1).filter(|_| has_input).cloned(),
+        input_string,
+        extra: positional.iter().skip(first_extra).cloned().collect(),
+        mem,
+        jobs,
+    }
+}
+
+/// How running one day went, kept until it can be printed in order.
+enum Solved {
+    Unreadable(ParseError),
+    Unparsable(String, ParseError),
+    Ran(String, Run),
+}
+
+fn solve(day: &Day, source: Result<Source, ParseError>, extra: &[String], parts: &[u32]) -> Solved {
+    let source = match source {
+        Ok(source) => source,
+        Err(e) => return Solved::Unreadable(e),
+    };
+    match day.run(&source, extra, parts) {
+        Ok(run) => Solved::Ran(source.name, run),
+        Err(e) => Solved::Unparsable(source.name, e),
+    }
+}
+
+/// Print one day's answers. Returns the total time spent, or `None` if the input
+/// couldn't be read or parsed.
+fn report(day: &Day, solved: Solved, format: Format, mem: bool) -> Option<Duration> {
+    let (path, run) = match solved {
+        Solved::Ran(path, run) => (path, run),
+        Solved::Unreadable(e) => {
+            format.say(&format!("Day {} (failed to read input)", day.day));
+            eprintln!("{}", e.diagnostic());
+            return None;
+        }
+        Solved::Unparsable(path, e) => {
+            format.say(&format!("Day {} ({}, failed to parse)", day.day, path));
+            eprintln!("{}", e.diagnostic());
+            return None;
+        }
+    };
+    let alloc = |stats: &AllocStats| if mem { format!(", {}", stats) } else { String::new() };
+    format.say(&format!(
+        "Day {} ({}, parsed in {}{})",
+        day.day,
+        path,
+        format_duration(run.parse_time),
+        alloc(&run.parse_alloc)
+    ));
+    let mut total = run.parse_time;
+    for part in run.parts {
+        match (format, &part.answer) {
+            (Format::Json, _) => println!("{}", part.to_json(day.day)),
+            (Format::Text, Some(answer)) => println!(
+                "  Part {}: {} ({}{})",
+                part.part,
+                answer,
+                format_duration(part.elapsed),
+                alloc(&part.alloc)
+            ),
+            (Format::Text, None) => println!("  Part {}: not implemented", part.part),
+        }
+        total += part.elapsed;
+    }
+    match run.extras {
+        Ok(lines) => {
+            for line in lines {
+                match format {
+                    Format::Json => println!("{{\"day\": {}, \"note\": {}}}", day.day, json_string(&line)),
+                    Format::Text => println!("  {}", line),
+                }
+            }
+            Some(total)
+        }
+        Err(e) => {
+            eprintln!("error: {}", e);
+            None
+        }
+    }
+}
+
+fn run(args: &[String]) {
+    let RunArgs {
+        day,
+        format,
+        parts,
+        input,
+        input_string,
+        extra,
+        mem,
+        jobs,
+    } = parse_run_args(args);
+    pool::set_jobs(jobs);
+
+    match day {
+        Some(d) => {
+            let day = find_day(d).unwrap_or_else(|| usage_error(&format!("No solution for day {}", d)));
+            let source = match input_string {
+                Some(text) => Ok(Source::new("<input-string>", &text)),
+                None => Source::read(&input.unwrap_or_else(|| day.default_input())),
+            };
+            if report(&day, solve(&day, source, &extra, &parts), format, mem).is_none() {
+                process::exit(1);
+            }
+        }
+        None => {
+            if input.is_some() || input_string.is_some() {
+                usage_error("'run all' always uses the default inputs");
+            }
+            let now = Instant::now();
+            let mut solve_time = Duration::default();
+            let mut failed = false;
+            let days = days();
+            let solved = pool::map(days.len(), |i| {
+                let path = days[i].default_input();
+                if !Path::new(&path).exists() {
+                    return None;
+                }
+                Some(solve(&days[i], Source::read(&path), &[], &parts))
+            });
+            for (day, solved) in days.iter().zip(solved) {
+                let solved = match solved {
+                    Some(solved) => solved,
+                    None => {
+                        format.say(&format!("Day {} (no input at {})", day.day, day.default_input()));
+                        continue;
+                    }
+                };
+                match report(day, solved, format, mem) {
+                    Some(elapsed) => solve_time += elapsed,
+                    None => failed = true,
+                }
+            }
+            format.say(&format!(
+                "\nSolved in {} ({} wall clock)",
+                format_duration(solve_time),
+                format_duration(now.elapsed())
+            ));
+            if failed {
+                process::exit(1);
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+struct Tally {
+    passed: usize,
+    failed: usize,
+    missing: usize,
+}
+
+/// Run one day on one input from the manifest and compare against the expected answers.
+fn verify_one(day: &Day, expected: &Expected, tally: &mut Tally) {
+    let args = expected.args.join(" ");
+    println!("Day {} ({}{}{})", day.day, expected.input, if args.is_empty() { "" } else { " " }, args);
+    if !Path::new(&expected.input).exists() {
+        println!("  missing: no input file");
+        tally.missing += 1;
+        return;
+    }
+
+    let run = Source::read(&expected.input).and_then(|source| day.run(&source, &expected.args, &[1, 2]));
+    let run = match run {
+        Ok(run) => run,
+        Err(e) => {
+            println!("  FAIL: couldn't parse input");
+            eprintln!("{}", e.diagnostic());
+            tally.failed += 1;
+            return;
+        }
+    };
+    for part in run.parts {
+        match (expected.part(part.part), part.answer) {
+            (Some(want), Some(got)) if *want == got => {
+                println!("  Part {}: pass ({})", part.part, got);
+                tally.passed += 1;
+            }
+            (Some(want), Some(got)) => {
+                println!("  Part {}: FAIL (expected {}, got {})", part.part, want, got);
+                tally.failed += 1;
+            }
+            (Some(want), None) => {
+                println!("  Part {}: FAIL (expected {}, but it's not implemented)", part.part, want);
+                tally.failed += 1;
+            }
+            (None, Some(got)) => {
+                println!("  Part {}: missing (got {}, but there's no expected answer)", part.part, got);
+                tally.missing += 1;
+            }
+            (None, None) => {}
+        }
+    }
+}
+
+fn verify(args: &[String]) {
+    let mut only_day = None;
+    let mut path = String::from(MANIFEST);
+    let mut i = 0;
+    while i < args.len() {
+        if args[i] == "--day" {
+            match args.get(i + 1).and_then(|d| d.parse::<u32>().ok()) {
+                Some(d) => only_day = Some(d),
+                None => usage_error("--day must be a number"),
+            }
+            i += 2;
+        } else {
+            path = args[i].clone();
+            i += 1;
+        }
+    }
+
+    let manifest = read_manifest(&path).unwrap_or_else(|e| {
+        eprintln!("{}", e.diagnostic());
+        process::exit(2);
+    });
+
+    let mut tally = Tally::default();
+    for day in days().iter().filter(|d| only_day.is_none_or(|n| n == d.day)) {
+        let entries: Vec<&Expected> = manifest.iter().filter(|e| e.day == day.day).collect();
+        if entries.is_empty() {
+            println!("Day {}: missing (no expected answers)", day.day);
+            tally.missing += 1;
+        }
+        for expected in entries {
+            verify_one(day, expected, &mut tally);
+        }
+    }
+    for expected in manifest.iter().filter(|e| only_day.is_none_or(|n| n == e.day)) {
+        if find_day(expected.day).is_none() {
+            println!("Day {} ({}): FAIL (no solution)", expected.day, expected.input);
+            tally.failed += 1;
+        }
+    }
+
+    println!(
+        "\n{} passed, {} failed, {} missing",
+        tally.passed, tally.failed, tally.missing
+    );
+    if tally.failed > 0 || tally.missing > 0 {
+        process::exit(1);
+    }
+}
+
+fn print_bench(b: &DayBench) {
+    println!("Day {} ({})", b.day, b.input);
+    for (name, stats, alloc) in b.phases.iter() {
+        println!(
+            "  {:<6} min {:>12}  median {:>12}  p95 {:>12}  {}",
+            format!("{}:", name),
+            format_duration(stats.min),
+            format_duration(stats.median),
+            format_duration(stats.p95),
+            alloc
+        );
+    }
+}
+
+fn bench(args: &[String]) {
+    let mut only_day = None;
+    let mut opts = BenchOptions::default();
+    let mut json = None;
+    let mut size = None;
+    let mut seed = 0;
+    let mut i = 0;
+    while i < args.len() {
+        let value = args.get(i + 1);
+        let number = || value.and_then(|v| v.parse::<usize>().ok());
+        match args[i].as_str() {
+            "--day" => match value.and_then(|d| d.parse::<u32>().ok()) {
+                Some(d) => only_day = Some(d),
+                None => usage_error("--day must be a number"),
+            },
+            "--warmup" => opts.warmup = number().unwrap_or_else(|| usage_error("--warmup must be a number")),
+            "--iterations" => match number() {
+                Some(n) if n > 0 => opts.iterations = n,
+                _ => usage_error("--iterations must be a positive number"),
+            },
+            "--json" => match value {
+                Some(path) => json = Some(path.clone()),
+                None => usage_error("--json needs a path"),
+            },
+            "--size" => match number() {
+                Some(n) if n > 0 => size = Some(n),
+                _ => usage_error("--size must be a positive number"),
+            },
+            "--seed" => seed = number().unwrap_or_else(|| usage_error("--seed must be a number")) as u64,
+            arg => usage_error(&format!("Unknown argument: {}", arg)),
+        }
+        i += 2;
+    }
+
+    let mut results = vec![];
+    let mut failed = false;
+    for day in days().iter().filter(|d| only_day.is_none_or(|n| n == d.day)) {
+        let (source, extra) = if let Some(size) = size {
+            match day.generate(seed, size) {
+                Some(g) => (Ok(Source::new(&format!("<size {} seed {}>", size, seed), &g.input)), g.args),
+                None => {
+                    println!("Day {} (no generator)", day.day);
+                    continue;
+                }
+            }
+        } else {
+            let mut path = day.default_input();
+            if !Path::new(&path).exists() {
+                path = format!("inputs/day{}.sample.txt", day.day);
+            }
+            if !Path::new(&path).exists() {
+                println!("Day {} (no input)", day.day);
+                continue;
+            }
+            (Source::read(&path), vec![])
+        };
+        let path = source.as_ref().map_or(String::new(), |s| s.name.clone());
+        match source.and_then(|source| day.bench(&source, &extra, &opts)) {
+            Ok(b) => {
+                print_bench(&b);
+                results.push(b);
+            }
+            Err(e) => {
+                println!("Day {} ({}, failed to parse)", day.day, path);
+                eprintln!("{}", e.diagnostic());
+                failed = true;
+            }
+        }
+    }
+
+    if let Some(path) = json {
+        let lines: Vec<String> = results.iter().map(|b| b.to_json(&opts) + "\n").collect();
+        if let Err(e) = fs::write(&path, lines.concat()) {
+            eprintln!("error: Couldn't write {}: {}", path, e);
+            process::exit(1);
+        }
+    }
+    if failed {
+        process::exit(1);
+    }
+}
+
+fn generate(args: &[String]) {
+    let mut day = None;
+    let mut seed = 0;
+    let mut size = 100;
+    let mut output = None;
+    let mut check = false;
+    let mut i = 0;
+    while i < args.len() {
+        let value = args.get(i + 1);
+        let number = || value.and_then(|v| v.parse::<u64>().ok());
+        match args[i].as_str() {
+            "--seed" => seed = number().unwrap_or_else(|| usage_error("--seed must be a number")),
+            "--size" => match number() {
+                Some(n) if n > 0 => size = n as usize,
+                _ => usage_error("--size must be a positive number"),
+            },
+            "--output" => match value {
+                Some(path) => output = Some(path.clone()),
+                None => usage_error("--output needs a path"),
+            },
+            "--check" => {
+                check = true;
+                i += 1;
+                continue;
+            }
+            arg => match (day, arg.parse::<u32>()) {
+                (None, Ok(d)) => {
+                    day = Some(d);
+                    i += 1;
+                    continue;
+                }
+                _ => usage_error(&format!("Unknown argument: {}", arg)),
+            },
+        }
+        i += 2;
+    }
+
+    let d = day.unwrap_or_else(|| usage_error("Expected a day"));
+    let day = find_day(d).unwrap_or_else(|| usage_error(&format!("No solution for day {}", d)));
+    let generated = match day.generate(seed, size) {
+        Some(g) => g,
+        None => {
+            eprintln!("error: There's no generator for day {}", d);
+            process::exit(1);
+        }
+    };
+
+    let planted = |answer: &Option<String>| answer.clone().unwrap_or_else(|| String::from("unknown"));
+    eprintln!(
+        "Day {} (seed {}, size {}): part 1 = {}, part 2 = {}{}",
+        d,
+        seed,
+        size,
+        planted(&generated.part1),
+        planted(&generated.part2),
+        if generated.args.is_empty() { String::new() } else { format!(", args: {}", generated.args.join(" ")) }
+    );
+    match output {
+        Some(path) => {
+            if let Err(e) = fs::write(&path, generated.input.clone() + "\n") {
+                eprintln!("error: Couldn't write {}: {}", path, e);
+                process::exit(1);
+            }
+        }
+        None if !check => println!("{}", generated.input),
+        None => {}
+    }
+    if !check {
+        return;
+    }
+
+    let source = Source::new(&format!("<day {} seed {}>", d, seed), &generated.input);
+    let run = match day.run(&source, &generated.args, &[1, 2]) {
+        Ok(run) => run,
+        Err(e) => {
+            println!("  FAIL: couldn't parse the generated input");
+            eprintln!("{}", e.diagnostic());
+            process::exit(1);
+        }
+    };
+    let mut failed = false;
+    for part in run.parts {
+        let want = if part.part == 1 { &generated.part1 } else { &generated.part2 };
+        match (want, part.answer) {
+            (Some(want), Some(got)) if *want == got => println!("  Part {}: pass ({})", part.part, got),
+            (Some(want), got) => {
+                println!("  Part {}: FAIL (expected {}, got {})", part.part, want, got.unwrap_or_default());
+                failed = true;
+            }
+            (None, Some(got)) => println!("  Part {}: {} (no planted answer)", part.part, got),
+            (None, None) => {}
+        }
+    }
+    if failed {
+        process::exit(1);
+    }
+}
+
+fn fuzz(args: &[String]) {
+    let mut only = None;
+    let mut cases = 10_000;
+    let mut seed = 0;
+    let mut dir = String::from("inputs");
+    let mut i = 0;
+    while i < args.len() {
+        let value = args.get(i + 1);
+        let number = || value.and_then(|v| v.parse::<u64>().ok());
+        match args[i].as_str() {
+            "--target" => match value.and_then(|t| fuzz::find_target(t)) {
+                Some(t) => only = Some(t.name),
+                None => usage_error(&format!("Unknown fuzz target: {}", value.map_or("", |v| v.as_str()))),
+            },
+            "--cases" => cases = number().unwrap_or_else(|| usage_error("--cases must be a number")),
+            "--seed" => seed = number().unwrap_or_else(|| usage_error("--seed must be a number")),
+            "--corpus" => match value {
+                Some(d) => dir = d.clone(),
+                None => usage_error("--corpus needs a directory"),
+            },
+            arg => usage_error(&format!("Unknown argument: {}", arg)),
+        }
+        i += 2;
+    }
+
+    // Keep the default hook from printing every caught panic; crashes are reported below.
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let mut failed = false;
+    for target in fuzz::targets().iter().filter(|t| only.is_none_or(|name| name == t.name)) {
+        let corpus = match fuzz::corpus(target, &dir) {
+            Ok(corpus) => corpus,
+            Err(e) => {
+                eprintln!("{}", e.diagnostic());
+                process::exit(1);
+            }
+        };
+        let start = Instant::now();
+        match fuzz::fuzz(target, &corpus, cases, seed) {
+            Ok(n) => println!(
+                "{}: {} inputs ({} from the corpus), no panics ({})",
+                target.name,
+                n,
+                corpus.len(),
+                format_duration(start.elapsed())
+            ),
+            Err(crash) => {
+                let case = crash.seed.map_or(String::from("corpus input"), |s| format!("seed {}", s));
+                println!("{}: panicked on {}: {}", target.name, case, crash.message);
+                println!("  input: {}", json_string(&crash.input));
+                failed = true;
+            }
+        }
+    }
+    panic::set_hook(hook);
+    if failed {
+        process::exit(1);
+    }
+}
+
+fn main() {
+    let args = log::take_flags(env::args().collect());
+    match args.get(1).map(|s| s.as_str()) {
+        Some("run") => run(&args[2..]),
+        Some("verify") => verify(&args[2..]),
+        Some("bench") => bench(&args[2..]),
+        Some("fuzz") => fuzz(&args[2..]),
+        Some("generate") => generate(&args[2..]),
+        Some("help") | Some("--help") | Some("-h") => println!("{}", USAGE),
+        Some(cmd) => usage_error(&format!("Unknown command: {}", cmd)),
+        None => usage_error("Expected a command"),
+    }
+}
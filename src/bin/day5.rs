@@ -0,0 +1,3 @@
+fn main() {
+    aoc2020::solution::main::<aoc2020::day5::Solver>();
+}
@@ -0,0 +1,219 @@
+use crate::error::{parse_at, ParseError};
+use crate::generate::Generated;
+use crate::pool;
+use crate::rng::Rng;
+use crate::solution::{check_flags, parse_flag, Solution};
+use crate::util::{self, Source};
+use crate::{debug, info};
+use itertools::Itertools;
+use std::collections::HashMap;
+
+pub fn parse_ints(text: &str) -> Result<Vec<i32>, ParseError> {
+  util::parse_text_lines(text, |line| parse_at(line, line))
+}
+
+pub fn read_ints(path: &str) -> Result<Vec<i32>, ParseError> {
+  Source::read(path)?.parse(parse_ints)
+}
+
+/// Every set of `k` distinct entries in `nums` which sum to `target`, as the lists of
+/// their indices. Each list is in increasing order, and so is the list of lists.
+///
+/// The entries are sorted first, so that pairs can be found with two pointers moving
+/// in from either end, and triples with a third pointer in front of them. For larger
+/// sets, each set is split in two, and the sums of the first halves go in a hash map
+/// for the second halves to look up.
+pub fn find_k_sum(nums: &[i32], k: usize, target: i64) -> Vec<Vec<usize>> {
+  let mut sorted: Vec<(i64, usize)> = nums.iter().enumerate().map(|(i, &n)| (n as i64, i)).collect();
+  sorted.sort_unstable();
+  let mut sets = match k {
+    0 if target == 0 => vec![vec![]],
+    0 => vec![],
+    1 => sorted.iter().filter(|&&(n, _)| n == target).map(|&(_, i)| vec![i]).collect(),
+    2 => {
+      let mut sets = vec![];
+      find_pairs(&sorted, 0, target, &[], &mut sets);
+      sets
+    }
+    // Each thread takes a first entry and looks for pairs after it.
+    3 => pool::map(sorted.len(), |a| {
+      let mut sets = vec![];
+      find_pairs(&sorted, a + 1, target - sorted[a].0, &[sorted[a].1], &mut sets);
+      sets
+    })
+    .concat(),
+    _ => meet_in_the_middle(&sorted, k, target),
+  };
+  for set in sets.iter_mut() {
+    set.sort_unstable();
+  }
+  sets.sort_unstable();
+  sets
+}
+
+/// Push every pair from `sorted[lo..]` which sums to `target` onto `sets`, after the
+/// indices in `prefix`.
+fn find_pairs(sorted: &[(i64, usize)], lo: usize, target: i64, prefix: &[usize], sets: &mut Vec<Vec<usize>>) {
+  if lo >= sorted.len() {
+    return;
+  }
+  let mut push = |a: usize, b: usize| sets.push([prefix, &[sorted[a].1, sorted[b].1]].concat());
+  let (mut i, mut j) = (lo, sorted.len() - 1);
+  while i < j {
+    let sum = sorted[i].0 + sorted[j].0;
+    if sum < target {
+      i += 1;
+    } else if sum > target {
+      j -= 1;
+    } else if sorted[i].0 == sorted[j].0 {
+      // Everything from i to j is the same, so any two of them will do.
+      for a in i..j {
+        for b in a + 1..=j {
+          push(a, b);
+        }
+      }
+      break;
+    } else {
+      // Pair up the runs of equal entries at both ends.
+      let run_end = (i..=j).find(|&a| sorted[a].0 != sorted[i].0).unwrap();
+      let run_start = (i..j).rev().find(|&b| sorted[b].0 != sorted[j].0).unwrap() + 1;
+      for a in i..run_end {
+        for b in run_start..=j {
+          push(a, b);
+        }
+      }
+      i = run_end;
+      j = run_start - 1;
+    }
+  }
+}
+
+/// The sets of `k` entries which sum to `target`. Each set, in sorted order, is split
+/// into its first `k / 2` entries and the rest, so it's found exactly once.
+fn meet_in_the_middle(sorted: &[(i64, usize)], k: usize, target: i64) -> Vec<Vec<usize>> {
+  let sum = |positions: &[usize]| positions.iter().map(|&p| sorted[p].0).sum::<i64>();
+  let mut firsts: HashMap<i64, Vec<Vec<usize>>> = HashMap::new();
+  for first in (0..sorted.len()).combinations(k / 2) {
+    firsts.entry(sum(&first)).or_default().push(first);
+  }
+  info!("{} sums of {} entries", firsts.len(), k / 2);
+
+  let mut sets = vec![];
+  for rest in (0..sorted.len()).combinations(k - k / 2) {
+    if let Some(firsts) = firsts.get(&(target - sum(&rest))) {
+      for first in firsts.iter().filter(|first| first[first.len() - 1] < rest[0]) {
+        sets.push(first.iter().chain(rest.iter()).map(|&p| sorted[p].1).collect());
+      }
+    }
+  }
+  sets
+}
+
+/// The product of the entries in the first set of `k` which sums to `target`, if there
+/// is one and the product fits in an i128.
+pub fn k_sum_product(expenses: &Expenses, k: usize) -> Option<i128> {
+  let sets = find_k_sum(&expenses.nums, k, expenses.target);
+  info!("{} set(s) of {} entries sum to {}", sets.len(), k, expenses.target);
+  for set in sets.iter() {
+    debug!("{:?}: {:?}", set, set.iter().map(|&i| expenses.nums[i]).collect::<Vec<_>>());
+  }
+  sets.first()?.iter().try_fold(1i128, |product, &i| product.checked_mul(expenses.nums[i] as i128))
+}
+
+/// `size` distinct expenses with exactly one triple that sums to 2020, even counting
+/// an expense twice. The other expenses are all over 1010, so no two of them fit in
+/// a triple, and none of them fits with two of the triple.
+pub fn generate_expenses(rng: &mut Rng, size: usize) -> Generated {
+  let triple = loop {
+    let a = rng.range(1..1011) as i32;
+    let b = rng.range(1..1011) as i32;
+    let c = 2020 - a - b;
+    let t = [a, b, c];
+    let sums = (0..3).flat_map(|i| (i..3).flat_map(move |j| (j..3).map(move |k| t[i] + t[j] + t[k])));
+    if a != b && (1..=1010).contains(&c) && c != a && c != b && sums.filter(|&s| s == 2020).count() == 1 {
+      break t;
+    }
+  };
+  let forbidden: Vec<i32> = triple.iter().flat_map(|x| triple.iter().map(move |y| 2020 - x - y)).collect();
+  let mut fillers: Vec<i32> = (1011..2020).filter(|n| !forbidden.contains(n)).collect();
+  rng.shuffle(&mut fillers);
+  fillers.truncate(size.max(3) - 3);
+
+  let mut nums = [&triple[..], &fillers].concat();
+  rng.shuffle(&mut nums);
+  Generated {
+    input: nums.iter().map(|n| n.to_string()).collect::<Vec<_>>().join("\n"),
+    part2: Some((triple[0] * triple[1] * triple[2]).to_string()),
+    ..Generated::default()
+  }
+}
+
+pub struct Expenses {
+  pub nums: Vec<i32>,
+  pub target: i64,
+  /// How many entries part 2 adds up.
+  pub k: usize,
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+  type Input = Expenses;
+  const DAY: u32 = 1;
+
+  /// Optional args are `--target T`, the sum to look for (default 2020), and `--k K`,
+  /// the number of entries for part 2 (default 3). Part 1 always looks for pairs.
+  fn parse(source: &Source, args: &[String]) -> Result<Expenses, ParseError> {
+    check_flags(args, &["--k", "--target"])?;
+    let k = parse_flag(args, "--k", 3)?;
+    let target = parse_flag(args, "--target", 2020)?;
+    let nums = source.parse(parse_ints)?;
+    info!("Read {} nums", nums.len());
+    Ok(Expenses { nums, target, k })
+  }
+
+  fn part1(expenses: &Expenses) -> Option<String> {
+    k_sum_product(expenses, 2).map(|n| n.to_string())
+  }
+
+  fn part2(expenses: &Expenses) -> Option<String> {
+    k_sum_product(expenses, expenses.k).map(|n| n.to_string())
+  }
+
+  fn generate(rng: &mut Rng, size: usize) -> Option<Generated> {
+    Some(generate_expenses(rng, size))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  // Note this useful idiom: importing names from outer (for mod tests) scope.
+  use super::*;
+  use crate::rng::for_all;
+
+  /// Every k-subset of the indices, by brute force.
+  fn brute_force(nums: &[i32], k: usize, target: i64) -> Vec<Vec<usize>> {
+    (0..nums.len()).combinations(k).filter(|set| set.iter().map(|&i| nums[i] as i64).sum::<i64>() == target).collect()
+  }
+
+  #[test]
+  fn test_find_k_sum() {
+    let nums = [1721, 979, 366, 299, 675, 1456];
+    assert_eq!(vec![vec![0, 3]], find_k_sum(&nums, 2, 2020));
+    assert_eq!(vec![vec![1, 2, 4]], find_k_sum(&nums, 3, 2020));
+    // An entry can't be used twice, but equal entries can be used together.
+    assert!(find_k_sum(&[1010, 5], 2, 2020).is_empty());
+    assert_eq!(vec![vec![0, 1], vec![0, 2], vec![1, 2]], find_k_sum(&[1010, 1010, 1010], 2, 2020));
+    assert_eq!(vec![Vec::<usize>::new()], find_k_sum(&nums, 0, 0));
+
+    // Lots of small, repeated entries, so that there are plenty of sets.
+    for_all(
+      200,
+      |rng| {
+        let nums: Vec<i32> = (0..rng.range(0..12)).map(|_| rng.range(-5..6) as i32).collect();
+        (nums, rng.range(1..6) as usize, rng.range(-8..9))
+      },
+      |(nums, k, target)| find_k_sum(nums, *k, *target) == brute_force(nums, *k, *target),
+    );
+  }
+}
@@ -0,0 +1,200 @@
+// use itertools::Itertools;
+use std::collections::HashMap;
+
+use crate::error::{parse_at, ParseError};
+use crate::generate::Generated;
+use crate::rng::Rng;
+use crate::solution::Solution;
+use crate::util::{self, Source};
+use crate::{debug, info};
+
+pub fn parse_nums(text: &str) -> Result<Vec<i32>, ParseError> {
+    util::parse_text_lines(text, |line| parse_at(line, line))
+}
+
+pub fn parse_file(path: &str) -> Result<Vec<i32>, ParseError> {
+    Source::read(path)?.parse(parse_nums)
+}
+
+pub fn count_diffs(seq: &[i32]) -> HashMap<i32, i32> {
+    let mut out = HashMap::new();
+    for i in 1..seq.len() {
+        let diff = seq[i - 1] - seq[i];
+        *out.entry(diff).or_insert(0) += 1;
+    }
+    out
+}
+
+pub fn find_seq(jolts: &[i32], current: i32, target: i32) -> Option<Vec<i32>> {
+    if current == target {
+        return Some(vec![]);
+    }
+
+    for (i, &next) in jolts.iter().enumerate().take(3) {
+        if next > target || next - current > 3 {
+            continue;
+        }
+        if let Some(mut seq) = find_seq(&jolts[(1+i)..], next, target) {
+            seq.push(next);
+            return Some(seq);
+        }
+    }
+
+    None
+}
+
+pub fn count_distinct(jolts: &[i32]) -> i64 {
+    if jolts.is_empty() {
+        return 0;
+    }
+    if jolts.len() == 1 {
+        return 1;
+    }
+
+    let current = *jolts.first().unwrap();
+    let target = *jolts.last().unwrap();
+    let mut num_distinct = 0;
+    for i in 1..jolts.len().min(4) {
+        let next = jolts[i];
+        if next > target || next - current > 3 {
+            continue;
+        }
+        if next == target {
+            num_distinct += 1;
+        } else {
+            num_distinct += count_distinct(&jolts[i..]);
+        }
+    }
+
+    num_distinct
+}
+
+pub fn count_distinct_fancy(jolts: &[i32]) -> i64 {
+    let mut mandatory: Vec<usize> = (1..jolts.len()).filter(|&i| jolts[i] - jolts[i - 1] == 3).collect();
+    if *mandatory.last().unwrap() != jolts.len() - 1 {
+        mandatory.push(jolts.len() - 1);
+    }
+    if mandatory[0] != 0 {
+        mandatory.insert(0, 0);
+    }
+
+    debug!("jolts: {:?}", jolts);
+    debug!("mandatory: {:?}", mandatory);
+
+    let ways = mandatory.windows(2).map(|x| {
+        let i = x[0];
+        let j = x[1];
+        let n = count_distinct(&jolts[i..=j]);
+        debug!("({}, {}) count_distinct: {:?}, cur={}, target={} --> {}",
+        i, j,
+        &jolts[i..=j], jolts[i], jolts[j], n);
+        n
+    });
+
+    ways.product()
+}
+
+/// Sort the adapters and add the charging outlet (0 jolts).
+pub fn to_jolts(nums: &[i32]) -> Vec<i32> {
+    let mut jolts = nums.to_vec();
+    jolts.insert(0, 0);
+    jolts.sort_unstable();
+    debug!("Jolts: {:?}", jolts);
+    jolts
+}
+
+pub fn diff_product(jolts: &[i32]) -> i32 {
+    let max = jolts.iter().max().unwrap();
+    let seq = find_seq(jolts, 0, *max).unwrap();
+    let diffs = count_diffs(&seq);
+    debug!("Sequence: {:?}", seq);
+    debug!("len = {}", seq.len());
+    debug!("max = {}", max);
+    debug!("diffs: {:?}", diffs);
+    // TODO: it feels so weird to be taking the address of a constant number.
+    //       or do I really really need to read this as "borrow"?
+    // The sequence already starts at the outlet; the device's built-in adapter is always 3 higher.
+    let a = diffs.get(&1).copied().unwrap_or(0);
+    let b = diffs.get(&3).copied().unwrap_or(0) + 1;
+    info!("answer: {} * {} = {}", a, b, a * b);
+    a * b
+}
+
+/// About `size` adapters in runs of up to four 1-jolt steps, each run ending with a
+/// 3-jolt step. The 3-jolt steps can't be skipped, so the arrangements of each run
+/// multiply, and a run of `r` steps has 1, 1, 2, 4 or 7 of them. It stops early
+/// rather than overflow the count.
+pub fn generate_adapters(rng: &mut Rng, size: usize) -> Generated {
+    const WAYS: [i64; 5] = [1, 1, 2, 4, 7];
+    let mut adapters = vec![];
+    let (mut jolts, mut ones, mut threes, mut ways) = (0, 0, 0, 1i64);
+    while adapters.len() < size.max(1) && ways < i64::MAX / 7 {
+        let run = rng.below(5) as usize;
+        for _ in 0..run {
+            jolts += 1;
+            adapters.push(jolts);
+        }
+        jolts += 3;
+        adapters.push(jolts);
+        ones += run;
+        threes += 1;
+        ways *= WAYS[run];
+    }
+    rng.shuffle(&mut adapters);
+
+    // The device's own adapter is another 3 jolts up.
+    Generated {
+        input: adapters.iter().map(|j| j.to_string()).collect::<Vec<_>>().join("\n"),
+        part1: Some((ones * (threes + 1)).to_string()),
+        part2: Some(ways.to_string()),
+        ..Generated::default()
+    }
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    type Input = Vec<i32>;
+    const DAY: u32 = 10;
+
+    fn parse(source: &Source, _args: &[String]) -> Result<Vec<i32>, ParseError> {
+        Ok(to_jolts(&source.parse(parse_nums)?))
+    }
+
+    fn part1(jolts: &Vec<i32>) -> Option<String> {
+        Some(diff_product(jolts).to_string())
+    }
+
+    fn part2(jolts: &Vec<i32>) -> Option<String> {
+        Some(count_distinct_fancy(jolts).to_string())
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated> {
+        Some(generate_adapters(rng, size))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+
+    #[test]
+    fn test_count_distinct() {
+        assert_eq!(count_distinct(&[14, 14]), 1);
+        assert_eq!(count_distinct(&[14, 17]), 1);
+        assert_eq!(count_distinct(&[14, 17, 20]), 1);
+        assert_eq!(count_distinct(&[14, 15, 17, 20]), 2);
+    }
+
+    #[test]
+    fn test_diff_product() {
+        let jolts = to_jolts(&[16, 10, 15, 5, 1, 11, 7, 19, 6, 12, 4]);
+        assert_eq!(diff_product(&jolts), 7 * 5);
+    }
+
+    #[test]
+    fn test_count_distinct4() {
+        assert_eq!(count_distinct(&[0, 1, 2, 3]), 4);
+    }
+}
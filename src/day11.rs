@@ -0,0 +1,190 @@
+use crate::error::ParseError;
+use crate::generate::Generated;
+use crate::grid::{Edges, Grid};
+use crate::rng::Rng;
+use crate::solution::Solution;
+use crate::util::Source;
+use crate::{debug, info};
+use std::collections::HashSet;
+use std::fmt;
+
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum Cell {
+    Empty,
+    Occupied,
+    Floor,
+}
+
+use Cell::*;
+
+impl fmt::Display for Cell {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Empty => "L",
+                Occupied => "#",
+                Floor => ".",
+            }
+        )
+    }
+}
+
+pub fn parse_char(c: char) -> Option<Cell> {
+    match c {
+        '.' => Some(Floor),
+        '#' => Some(Occupied),
+        'L' => Some(Empty),
+        _ => None,
+    }
+}
+
+pub type Ferry = Grid<Cell>;
+
+pub fn parse_ferry(text: &str) -> Result<Ferry, ParseError> {
+    Grid::parse(text, parse_char)
+}
+
+pub fn num_neighbors(ferry: &Ferry, x: usize, y: usize) -> usize {
+    ferry
+        .neighbors8(x, y, Edges::Clip)
+        .filter(|&(_, &c)| c == Occupied)
+        .count()
+}
+
+pub fn next_state(ferry: &Ferry, x: usize, y: usize) -> Cell {
+    let c = ferry[(x, y)];
+    let n = num_neighbors(ferry, x, y);
+
+    match c {
+        Occupied if n >= 4 => Empty,
+        Empty if n == 0 => Occupied,
+        c => c,
+    }
+}
+
+pub fn advance(ferry: &Ferry) -> Ferry {
+    Grid::from_fn(ferry.width(), ferry.height(), |x, y| next_state(ferry, x, y))
+}
+
+pub fn num_occ(ferry: &Ferry) -> i32 {
+    ferry.iter().filter(|&(_, &cell)| cell == Occupied).count() as i32
+}
+
+/// The number of occupied seats once the ferry stops changing, or None if it ends up
+/// going round in a cycle instead.
+pub fn settle(ferry: &Ferry) -> Option<i32> {
+    let mut ferry = ferry.clone();
+    debug!("Ferry:\n{}", ferry);
+
+    let mut seen = HashSet::new();
+    let mut n = 0;
+    loop {
+        n += 1;
+        let next = advance(&ferry);
+        debug!("\n{} occupied: {}\n{}\n---", n, num_occ(&next), next);
+        if next == ferry {
+            break;
+        }
+        if !seen.insert(ferry) {
+            info!("{}, back to an earlier state, so it never settles", n);
+            return None;
+        }
+        ferry = next;
+    }
+    debug!("{}", ferry);
+    info!("{}, occupied: {}", n, num_occ(&ferry));
+    Some(num_occ(&ferry))
+}
+
+/// A waiting area about `size` rows deep, with about one square in eight floor.
+/// Big random layouts rarely settle, so it's made of bands of up to ten rows with a
+/// row of floor between them. Each band is redrawn until it settles on its own, and
+/// since the bands can't see each other, the whole ferry settles too. There's no
+/// shortcut to the answers, so they're left to the solver.
+pub fn generate_ferry(rng: &mut Rng, size: usize) -> Generated {
+    let mut rows: Vec<String> = vec![];
+    while rows.len() < size.max(1) {
+        if !rows.is_empty() {
+            rows.push(".".repeat(90));
+        }
+        let height = (size.max(1) - rows.len()).min(10);
+        let band = loop {
+            let band = Grid::from_fn(90, height, |_, _| if rng.below(8) == 0 { Floor } else { Empty });
+            if settle(&band).is_some() {
+                break band;
+            }
+        };
+        rows.extend(band.to_string().lines().map(String::from));
+    }
+    Generated::new(rows.join("\n"))
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    type Input = Ferry;
+    const DAY: u32 = 11;
+
+    fn parse(source: &Source, _args: &[String]) -> Result<Ferry, ParseError> {
+        source.grid(parse_char)
+    }
+
+    fn part1(ferry: &Ferry) -> Option<String> {
+        settle(ferry).map(|occupied| occupied.to_string())
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated> {
+        Some(generate_ferry(rng, size))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+
+    #[test]
+    fn advance_33() {
+        // #.#
+        // ###
+        let f: Ferry = Grid::from_rows(vec![
+            vec![Occupied, Floor, Occupied, Occupied],
+            vec![Occupied, Occupied, Occupied, Occupied],
+        ]);
+        // #.L
+        // #LL
+        assert_eq!(next_state(&f, 0, 0), Occupied);
+        assert_eq!(next_state(&f, 1, 0), Floor);
+        assert_eq!(next_state(&f, 2, 0), Empty);
+    }
+
+    #[test]
+    fn test_num_neighbors() {
+        // #.##
+        // ####
+        let f: Ferry = Grid::from_rows(vec![
+            vec![Occupied, Floor, Occupied, Occupied],
+            vec![Occupied, Occupied, Occupied, Occupied],
+        ]);
+        // #.L
+        // #LL
+        assert_eq!(num_neighbors(&f, 0, 0), 2);
+        assert_eq!(num_neighbors(&f, 1, 0), 5);
+        assert_eq!(num_neighbors(&f, 2, 0), 4);
+    }
+
+    #[test]
+    fn test_generate_settles() {
+        // Big random layouts, like this one, tend to end up flipping between two states.
+        let mut rng = Rng::new(1);
+        let ferry = Grid::from_fn(90, 100, |_, _| if rng.below(8) == 0 { Floor } else { Empty });
+        assert_eq!(None, settle(&ferry));
+
+        for seed in 0..3 {
+            let generated = generate_ferry(&mut Rng::new(seed), 100);
+            assert!(settle(&parse_ferry(&generated.input).unwrap()).is_some());
+        }
+    }
+}
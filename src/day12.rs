@@ -0,0 +1,222 @@
+use crate::error::{parse_at, ParseError};
+use crate::generate::Generated;
+use crate::rng::Rng;
+use crate::solution::Solution;
+use crate::util::{self, Source};
+use crate::{debug, info};
+
+#[derive(Debug)]
+struct Ship {
+    x: i32,
+    y: i32,
+    wdx: i32,
+    wdy: i32,
+    /// degrees, 0 = east
+    dir: i32,
+}
+
+#[derive(Debug)]
+pub enum Action {
+    F(i32),
+    N(i32),
+    S(i32),
+    E(i32),
+    W(i32),
+    L(i32),
+    R(i32),
+}
+
+pub fn parse_action(s: &str) -> Result<Action, ParseError> {
+    let c = s.chars().next().ok_or_else(|| ParseError::whole(s, "Expected an action"))?;
+    let arg_str = &s[c.len_utf8()..];
+    let arg = parse_at::<i32>(s, arg_str)?;
+    if (c == 'L' || c == 'R') && arg % 90 != 0 {
+        return Err(ParseError::at(s, arg_str, "Turns must be multiples of 90 degrees"));
+    }
+    match c {
+        'N' => Ok(Action::N(arg)),
+        'S' => Ok(Action::S(arg)),
+        'E' => Ok(Action::E(arg)),
+        'W' => Ok(Action::W(arg)),
+        'L' => Ok(Action::L(arg)),
+        'R' => Ok(Action::R(arg)),
+        'F' => Ok(Action::F(arg)),
+        _ => Err(ParseError::new(s, 1, 1, &format!("Invalid action: {}", c))),
+    }
+}
+
+pub fn dir(degrees: i32) -> (i32, i32) {
+    match (degrees + 3600) % 360 {
+        0 => (1, 0),
+        90 => (0, 1),
+        180 => (-1, 0),
+        270 => (0, -1),
+        _ => unreachable!()
+    }
+}
+
+pub fn rot(d: (i32, i32), degrees: i32) -> (i32, i32) {
+    let mut degrees = degrees.rem_euclid(360);
+    let (mut dx, mut dy) = d;
+
+    while degrees > 0 {
+        degrees -= 90;
+        let ndx = -dy;
+        let ndy = dx;
+        dx = ndx;
+        dy = ndy;
+    }
+    assert_eq!(0, degrees);
+
+    (dx, dy)
+}
+
+pub fn parse_actions(text: &str) -> Result<Vec<Action>, ParseError> {
+    util::parse_text_lines(text, parse_action)
+}
+
+pub fn read_actions(path: &str) -> Result<Vec<Action>, ParseError> {
+    Source::read(path)?.parse(parse_actions)
+}
+
+/// Part 1: the actions move the ship directly.
+pub fn sail(actions: &[Action]) -> i32 {
+    let mut ship = Ship { x: 0, y: 0, wdx: 0, wdy: 0, dir: 0 };
+    for action in actions {
+        match *action {
+            Action::N(arg) => ship.y += arg,
+            Action::S(arg) => ship.y -= arg,
+            Action::E(arg) => ship.x += arg,
+            Action::W(arg) => ship.x -= arg,
+            Action::L(arg) => ship.dir = (ship.dir + arg).rem_euclid(360),
+            Action::R(arg) => ship.dir = (ship.dir - arg).rem_euclid(360),
+            Action::F(arg) => {
+                let (dx, dy) = dir(ship.dir);
+                ship.x += arg * dx;
+                ship.y += arg * dy;
+            }
+        }
+    }
+    ship.x.abs() + ship.y.abs()
+}
+
+/// Part 2: most actions move the waypoint, which is relative to the ship.
+pub fn navigate(actions: &[Action]) -> i32 {
+    let mut ship = Ship { x: 0, y: 0, wdx: 10, wdy: 1, dir: 0 };
+    for action in actions {
+        match *action {
+            Action::N(arg) => ship.wdy += arg,
+            Action::S(arg) => ship.wdy -= arg,
+            Action::E(arg) => ship.wdx += arg,
+            Action::W(arg) => ship.wdx -= arg,
+            Action::L(arg) => {
+                let (dx, dy) = rot((ship.wdx, ship.wdy), arg);
+                ship.wdx = dx;
+                ship.wdy = dy;
+            },
+            Action::R(arg) => {
+                let (dx, dy) = rot((ship.wdx, ship.wdy), -arg);
+                ship.wdx = dx;
+                ship.wdy = dy;
+            },
+            Action::F(arg) => {
+                ship.x += arg * ship.wdx;
+                ship.y += arg * ship.wdy;
+            }
+        }
+        debug!("{:?} -> {:?}", &action, &ship);
+    }
+    info!(
+        "Manhattan distance: {} + {} = {}",
+        ship.x.abs(), ship.y.abs(),
+        ship.x.abs() + ship.y.abs(),
+    );
+    ship.x.abs() + ship.y.abs()
+}
+
+/// `size` random actions, with turns in multiples of 90 degrees. Nothing is planted,
+/// since working out where the ship ends up is the whole puzzle.
+pub fn generate_actions(rng: &mut Rng, size: usize) -> Generated {
+    let actions: Vec<String> = (0..size)
+        .map(|_| match rng.below(8) {
+            0 | 1 => format!("{}{}", rng.choose(&['L', 'R']), 90 * rng.range(1..4)),
+            2 | 3 => format!("F{}", rng.range(1..101)),
+            _ => format!("{}{}", rng.choose(&['N', 'S', 'E', 'W']), rng.range(1..6)),
+        })
+        .collect();
+    Generated::new(actions.join("\n"))
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    type Input = Vec<Action>;
+    const DAY: u32 = 12;
+
+    fn parse(source: &Source, _args: &[String]) -> Result<Vec<Action>, ParseError> {
+        source.parse(parse_actions)
+    }
+
+    fn part1(actions: &Vec<Action>) -> Option<String> {
+        Some(sail(actions).to_string())
+    }
+
+    fn part2(actions: &Vec<Action>) -> Option<String> {
+        Some(navigate(actions).to_string())
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated> {
+        Some(generate_actions(rng, size))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+    use crate::rng::{for_all, Rng};
+
+    #[test]
+    fn test_mod() {
+        let x = -270 - 360;
+        assert_eq!(-270, x % 360);
+
+        assert_eq!(90, ((x % 360) + 360) % 360);
+    }
+
+    #[test]
+    fn test_rot() {
+        assert_eq!((2, 1), rot((2, 1), 0));
+        assert_eq!((-1, 2), rot((2, 1), 90));
+        assert_eq!((-2, -1), rot((2, 1), 180));
+        assert_eq!((1, -2), rot((2, 1), 270));
+        assert_eq!((1, -2), rot((2, 1), -90));
+        assert_eq!((-2, -1), rot((2, 1), -180));
+        assert_eq!((-1, 2), rot((2, 1), -270));
+        assert_eq!((2, 1), rot((2, 1), 360));
+        //        (dx, dy)
+        // +90 -> (-dy, dx)
+        // +180 -> (-dx, -dy)
+        // +270 -> (dy, -dx)
+    }
+
+    fn random_turn(rng: &mut Rng) -> i32 {
+        90 * rng.range(-8..9) as i32
+    }
+
+    #[test]
+    fn test_rot_props() {
+        let gen = |rng: &mut Rng| {
+            let d = (rng.range(-100..101) as i32, rng.range(-100..101) as i32);
+            (d, random_turn(rng), random_turn(rng))
+        };
+        for_all(500, gen, |&(d, a, b)| rot(d, a + b) == rot(rot(d, a), b));
+        for_all(500, gen, |&(d, a, _)| rot(rot(d, a), -a) == d);
+        for_all(500, gen, |&(d, a, _)| rot(d, a + 360) == rot(d, a));
+        for_all(500, gen, |&(d, a, _)| {
+            let (x, y) = rot(d, a);
+            x * x + y * y == d.0 * d.0 + d.1 * d.1
+        });
+        for_all(500, random_turn, |&a| dir(a) == rot((1, 0), a));
+    }
+}
@@ -0,0 +1,223 @@
+use crate::error::{parse_at, ParseError};
+use crate::generate::{self, Generated};
+use crate::rng::Rng;
+use crate::solution::Solution;
+use crate::util::Source;
+use crate::{debug, info};
+
+/// Find the first n such that:
+///   n = t1 (mod p1)
+///   n = t2 (mod p2)
+///
+/// Subsequent ns will be n = n + k*p1*p2
+pub fn first_congruence(p1: u64, t1: u64, p2: u64, t2: u64) -> u64 {
+    if p1 < p2 {
+        return first_congruence(p2, t2, p1, t1);
+    }
+    for k1 in 0..p2 {
+        let n = k1 * p1 + t1;
+        if n % p2 == t2 {
+            return n;
+        }
+    }
+    panic!("Unable to find congruence: {} {}", p1, p2);
+}
+
+/// The earliest departure time and the bus IDs, paired with the offset (mod the ID)
+/// at which each must depart.
+pub struct Schedule {
+    pub t0: u64,
+    pub buses: Vec<(u64, u64)>,
+}
+
+fn parse_bus(line: &str, id: &str) -> Result<u64, ParseError> {
+    match parse_at::<u64>(line, id)? {
+        0 => Err(ParseError::at(line, id, "Bus IDs must be positive")),
+        p => Ok(p),
+    }
+}
+
+pub fn parse_schedule(text: &str) -> Result<Schedule, ParseError> {
+    let mut lines = text.lines();
+    let first = lines.next().unwrap_or("");
+    let t0 = parse_at::<u64>(first, first)?;
+    let second = lines
+        .next()
+        .ok_or_else(|| ParseError::general("Expected a line of bus IDs"))?;
+    let mut pt = second
+        .split(',')
+        .enumerate()
+        .filter(|(_, x)| *x != "x")
+        .map(|(t, x)| Ok((parse_bus(second, x)?, t as u64)))
+        .map(|r: Result<_, ParseError>| r.map(|(p, t)| (p, (p - (t % p)) % p)))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.shift(1))?;
+    if pt.is_empty() {
+        return Err(ParseError::whole(second, "Expected at least one bus").shift(1));
+    }
+    // pt.sort();
+    pt = pt.into_iter().rev().collect();
+
+    Ok(Schedule { t0, buses: pt })
+}
+
+pub fn read_schedule(path: &str) -> Result<Schedule, ParseError> {
+    Source::read(path)?.parse(parse_schedule)
+}
+
+pub fn earliest_bus(schedule: &Schedule) -> u64 {
+    let t0 = schedule.t0;
+    let (wait, p) = schedule.buses.iter().map(|&(p, _)| ((p - t0 % p) % p, p)).min().unwrap();
+    info!("Answer (part 1): {} * {} = {}", wait, p, wait * p);
+    wait * p
+}
+
+pub fn earliest_timestamp(schedule: &Schedule) -> u64 {
+    let pt = &schedule.buses;
+    debug!("t0: {}", schedule.t0);
+    debug!("primes: {:?}", pt);
+
+    let (mut p, mut t) = pt[0];
+    assert!(t != 0);
+    // let mut p0 = 0;
+    for &(pi, ti) in &pt[1..] {
+        // if ti == 0 {
+        //     p0 = pi;
+        //     continue;
+        // }
+        t = first_congruence(p, t, pi, ti);
+        p *= pi;
+    }
+    info!("{} mod {}", t, p);
+    t
+
+    // for w in tp.windows(2) {
+    //     // Keep getting "refutable pattern in binding" errors here:
+    //     // let &[(p1, t1), (p2, t2)] = w;
+    //     let (p1, t1) = w[0];
+    //     let (p2, t2) = w[1];
+    //     println!("base ({}, {}) = {}", p1, p2, first_congruence(p1, t1 as u64, p2, t2 as u64));
+    // }
+
+    // let n = first_congruence(59 * 31, 592, 19 * 13, 118);
+    // println!("fc 592, 118 = {}", n);
+    // println!("fc 7 next = {}", first_congruence(7, 0, 59 * 31 * 19 * 13, n))
+    // primes: [(59, 2), (31, 3), (19, 4), (13, 1), (7, 0)]
+    // base (59, 31) = 592
+    // base (31, 19) = 251
+    // base (19, 13) = 118
+    // base (13, 7) = 14
+    // 181663
+
+    // t = 0 (mod p1)
+    // t = 1 (mod p2)
+    // -> t = 0 (mod p1 * p2)
+    // -> p1 * t = p1 (mod p2)
+
+    // Find the first one, then subsequent ones will be by adding p1 * p2
+    // 11 * 59 + 2 = 651 = 1 (mod 13)
+    // 24 * 59 + 2 = 1418 = 1 (mod 13)
+    // 37 * 59 + 2 = 2185 = 1 (mod 13)
+    // 50 * 59 + 2 = 2952 = 1 (mod 13)
+    // 63 * 59 + 2 = 3719 = 1 (mod 13)
+    // 76 * 59 + 2 = 4486 = 1 (mod 13)
+    // 89 * 59 + 2 = 5253 = 1 (mod 13)
+
+    // t = 0 (mod 7)
+    // t = 1 (mod 13)
+    // t = 2 (mod 59)
+    // t = 3 (mod 31)
+    // t = 4 (mod 19)
+
+    // t = 59k2 + 2
+    // 7k1 = 59k2 + 2
+    //
+
+    /*
+    for p in primes {
+        let last = p * (t0 / p);
+        let wait = p - (t0 % p);
+        eprintln!("{}, wait {} * {} -> {}", last, wait, p, wait * p);
+    }
+    */
+}
+
+/// A random schedule with up to `size` buses, with both answers planted.
+///
+/// The bus IDs are distinct primes whose product fits comfortably in a u64. The
+/// answer to part 2 is picked first, and then each bus goes at the first position
+/// after the previous one which departs at the right offset from it.
+pub fn generate_schedule(rng: &mut Rng, size: usize) -> Generated {
+    let mut primes = generate::primes_below(1000);
+    primes.retain(|&p| p > 5);
+    rng.shuffle(&mut primes);
+    let mut ids = vec![];
+    let mut product: u64 = 1;
+    for &p in &primes {
+        if ids.len() == size.max(2) || product * p > 1_000_000_000_000_000 {
+            break;
+        }
+        ids.push(p);
+        product *= p;
+    }
+
+    // The first bus leaves at t itself, and none of the others can, since then the
+    // last one listed might too.
+    let t = loop {
+        let t = ids[0] * rng.range(1..(product / ids[0]) as i64) as u64;
+        if ids[1..].iter().all(|&p| !t.is_multiple_of(p)) {
+            break t;
+        }
+    };
+    let mut slots = vec![ids[0].to_string()];
+    for &p in &ids[1..] {
+        let next = slots.len() as u64;
+        let gap = (p - (t + next) % p) % p;
+        slots.extend((0..gap).map(|_| String::from("x")));
+        slots.push(p.to_string());
+    }
+
+    let t0 = rng.range(1..1_000_000_000) as u64;
+    let (wait, p) = ids.iter().map(|&p| ((p - t0 % p) % p, p)).min().unwrap();
+    Generated {
+        input: format!("{}\n{}", t0, slots.join(",")),
+        part1: Some((wait * p).to_string()),
+        part2: Some(t.to_string()),
+        ..Generated::default()
+    }
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    type Input = Schedule;
+    const DAY: u32 = 13;
+
+    fn parse(source: &Source, _args: &[String]) -> Result<Schedule, ParseError> {
+        source.parse(parse_schedule)
+    }
+
+    fn part1(schedule: &Schedule) -> Option<String> {
+        Some(earliest_bus(schedule).to_string())
+    }
+
+    fn part2(schedule: &Schedule) -> Option<String> {
+        Some(earliest_timestamp(schedule).to_string())
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated> {
+        Some(generate_schedule(rng, size))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+
+    #[test]
+    fn test_find_congruence() {
+        assert_eq!(first_congruence(59, 2, 13, 1), 651);
+        assert_eq!(first_congruence(13, 1, 59, 2), 651);
+    }
+}
@@ -0,0 +1,202 @@
+use crate::error::{parse_at, ParseError};
+use crate::generate::Generated;
+use crate::rng::Rng;
+use crate::solution::Solution;
+use crate::util::{self, Source};
+use crate::debug;
+use regex::Regex;
+use std::collections::HashMap;
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum Op {
+    Mask{ones: u64, zeros: u64, xs: Vec<u32>},
+    Mem{addr: u64, value: u64},
+}
+
+lazy_static! {
+    static ref MASK_RE: Regex = Regex::new(r"^mask = ([X01]{36})$").unwrap();
+    static ref SET_RE: Regex = Regex::new(r"^mem\[(\d+)] = (\d+)$").unwrap();
+}
+
+pub fn parse_instruction(text: &str) -> Result<Op, ParseError> {
+    if let Some(groups) = SET_RE.captures(text) {
+        let addr = parse_at::<u64>(text, &groups[1])?;
+        let value = parse_at::<u64>(text, &groups[2])?;
+        return Ok(Op::Mem { addr, value })
+    }
+
+    if let Some(groups) = MASK_RE.captures(text) {
+        let raw = &groups[1];
+        let xs: Vec<u32> = raw.chars().enumerate().filter(|&(_i, b)| b == 'X').map(|(i, _b)| (35 - i) as u32).collect();
+        let ones = u64::from_str_radix(&raw.chars().map(|b| if b == '1' { '1' } else { '0' }).collect::<String>(), 2).unwrap();
+        let zeros = u64::from_str_radix(&raw.chars().map(|b| if b == '0' { '1' } else { '0' }).collect::<String>(), 2).unwrap();
+
+        return Ok(Op::Mask { xs, ones, zeros })
+    }
+
+    if let Some(mask) = text.strip_prefix("mask = ") {
+        return Err(ParseError::at(text, mask, "Expected a mask of 36 Xs, 0s and 1s"));
+    }
+    Err(ParseError::whole(text, "Bad instruction"))
+}
+
+pub fn parse_program(text: &str) -> Result<Vec<Op>, ParseError> {
+    util::parse_text_lines(text, parse_instruction)
+}
+
+pub fn read_program(path: &str) -> Result<Vec<Op>, ParseError> {
+    Source::read(path)?.parse(parse_program)
+}
+
+pub fn enumerate_xs(xs: &[u32]) -> Vec<u64> {
+    let mut out = Vec::new();
+    for i in 0..2u32.pow(xs.len() as u32) {
+        let mut v = 0u64;
+        for k in 0..32 {
+            if i & (1 << k) != 0 {
+                v += 1u64 << xs[k as usize];
+            }
+        }
+        out.push(v);
+    }
+    out
+}
+
+pub fn xs_to_mask(xs: &[u32]) -> u64 {
+    let mut out = 0u64;
+    for x in xs {
+        out += 1u64 << x;
+    }
+    out
+}
+
+/// Runs the program with floating address bits and returns the final memory.
+pub fn run_program(ops: &[Op]) -> HashMap<u64, u64> {
+    let mut mem: HashMap<u64, u64> = HashMap::new();
+    // interesting that you can't make the type a specific variant of the enum
+    // let mut mask = Op::Mask { set: 0, mask: 0 };
+    let mut cur_ones = 0u64;
+    // let mut cur_zeros = 0u64;
+    let mut cur_xs: &[u32] = &[];
+
+    for op in ops {
+        match op {
+            Op::Mask { ones, zeros: _, xs } => {
+                cur_ones = *ones;
+                // cur_zeros = *zeros;
+                cur_xs = xs;
+            }
+            Op::Mem { mut addr, value } => {
+                // If the bitmask bit is 0, the corresponding memory address bit is unchanged.
+                // If the bitmask bit is 1, the corresponding memory address bit is overwritten with 1.
+                // If the bitmask bit is X, the corresponding memory address bit is floating.
+                // mem.insert(*addr, (value & cur_xs) | cur_ones);
+                debug!("addr: {}", addr);
+                addr = (addr & !cur_ones) | cur_ones;
+                debug!("addr+ones: {}", addr);
+                let mask = xs_to_mask(cur_xs);
+                addr &= !mask;
+                debug!("addr+ones+mask: {}", addr);
+                for float in enumerate_xs(cur_xs) {
+                    let x = addr | (float & mask);
+                    mem.insert(x, *value);
+                    debug!("Write @{} value {}", x, *value);
+                }
+            }
+        }
+    }
+
+    mem
+}
+
+/// A program of `size` instructions, with a new mask every five or so writes. The
+/// masks have at most nine floating bits, so no write touches more than 512
+/// addresses, and the answer comes from running it with a plain loop over the
+/// subsets of the floating bits.
+pub fn generate_program(rng: &mut Rng, size: usize) -> Generated {
+    let mut lines = vec![];
+    let mut mem: HashMap<u64, u64> = HashMap::new();
+    let (mut ones, mut floating) = (0u64, 0u64);
+    for i in 0..size.max(1) {
+        if i == 0 || rng.below(5) == 0 {
+            let mut bits: Vec<char> = (0..36).map(|_| if rng.bool() { '1' } else { '0' }).collect();
+            for _ in 0..rng.range(0..10) {
+                bits[rng.below(36) as usize] = 'X';
+            }
+            ones = bits.iter().fold(0, |acc, &b| 2 * acc + (b == '1') as u64);
+            floating = bits.iter().fold(0, |acc, &b| 2 * acc + (b == 'X') as u64);
+            lines.push(format!("mask = {}", bits.into_iter().collect::<String>()));
+            continue;
+        }
+
+        let addr = rng.below(1 << 16);
+        let value = rng.below(1_000_000);
+        lines.push(format!("mem[{}] = {}", addr, value));
+        let base = (addr | ones) & !floating;
+        let mut subset = floating;
+        loop {
+            mem.insert(base | subset, value);
+            if subset == 0 {
+                break;
+            }
+            subset = (subset - 1) & floating;
+        }
+    }
+    Generated {
+        input: lines.join("\n"),
+        part2: Some(mem.values().sum::<u64>().to_string()),
+        ..Generated::default()
+    }
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    type Input = Vec<Op>;
+    const DAY: u32 = 14;
+
+    fn parse(source: &Source, _args: &[String]) -> Result<Vec<Op>, ParseError> {
+        source.parse(parse_program)
+    }
+
+    fn part2(program: &Vec<Op>) -> Option<String> {
+        let mem = run_program(program);
+        debug!("Memory: {:?}", mem);
+        Some(mem.values().sum::<u64>().to_string())
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated> {
+        Some(generate_program(rng, size))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+
+    #[test]
+    fn test_parse_instruction() {
+        assert_eq!(parse_instruction("mem[7] = 101"), Ok(Op::Mem { addr: 7, value: 101 }));
+        let err = parse_instruction("mask = XX01").unwrap_err();
+        assert_eq!((8, 4), (err.column, err.len));
+        assert_eq!("Bad instruction", parse_instruction("mem[7] = x").unwrap_err().message);
+    }
+
+    #[test]
+    fn test_enumerate_xs() {
+        assert_eq!(enumerate_xs(&[0, 1]), vec![0, 1, 2, 3]);
+        assert_eq!(enumerate_xs(&[0, 2]), vec![0, 1, 4, 5]);
+    }
+    // #[test]
+    // fn test_parse_instr() {
+    //     assert_eq!(parse_instruction("mem[7] = 101"), Op::Mem { addr: 7, value: 101 });
+    //     assert_eq!(parse_instruction(
+    //         "mask = XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X"),
+    //         //                                   4268421
+    //         //                                   631
+    //         Op::Mask { xs: 0b111111111111111111111111111110111101, ones: 64, zeros: 2 }
+    //     );
+    //     // assert_eq!(parse_instruction("jmp -4"), Op::Jmp(-4));
+    // }
+}
@@ -0,0 +1,100 @@
+// use std::collections::HashMap;
+// use rustc_hash::FxHashMap;
+
+use crate::error::{parse_at, ParseError};
+use crate::generate::Generated;
+use crate::rng::Rng;
+use crate::solution::Solution;
+use crate::util::Source;
+use crate::{debug, info};
+
+pub fn play_game(start: &[i32], num_rounds: i32) -> i32 {
+    // let mut num_to_round: HashMap<i32, i32> = HashMap::new();
+    // let mut num_to_round: FxHashMap<i32, i32> = FxHashMap::default();
+    let mut num_to_round = vec![-1; num_rounds as usize];
+    let mut last_spoken: i32 = 0;
+    for (n, &i) in start.iter().enumerate() {
+        if n > 0 {
+            num_to_round[last_spoken as usize] = (n as i32) - 1;
+        }
+        last_spoken = i;
+        debug!("{}: {}", 1 + n, last_spoken);
+    }
+
+    for i in (start.len() as i32)..num_rounds {
+        // TODO: is it possible to do the insert immediately after the lookup?
+        //       the borrow checker seems unhappy with that. Can I copy the option?
+        let last = num_to_round[last_spoken as usize];
+        num_to_round[last_spoken as usize] = i - 1;
+        if last >= 0 {
+            debug!("{}  {} last spoken at {}", i, last_spoken, last);
+            last_spoken = i - last - 1;
+        } else {
+            debug!("{}  {} never spoken before", i, last_spoken);
+            last_spoken = 0;
+        }
+        debug!("{}: {}", 1 + i, last_spoken);
+    }
+
+    last_spoken
+}
+
+pub fn parse_start(line: &str) -> Result<Vec<i32>, ParseError> {
+    line.split(',')
+        .map(|s| match parse_at::<i32>(line, s)? {
+            n if n < 0 => Err(ParseError::at(line, s, "Starting numbers can't be negative")),
+            n => Ok(n),
+        })
+        .collect()
+}
+
+pub fn read_start(path: &str) -> Result<Vec<i32>, ParseError> {
+    Source::read(path)?.parse(parse_start)
+}
+
+/// `size` distinct starting numbers, up to 100. The game is the same length
+/// whatever the start, so this only varies which numbers come up.
+pub fn generate_start(rng: &mut Rng, size: usize) -> Generated {
+    let mut nums: Vec<i32> = (0..100).collect();
+    rng.shuffle(&mut nums);
+    nums.truncate(size.clamp(1, 100));
+    Generated::new(nums.iter().map(|n| n.to_string()).collect::<Vec<_>>().join(","))
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    type Input = Vec<i32>;
+    const DAY: u32 = 15;
+
+    fn parse(source: &Source, _args: &[String]) -> Result<Vec<i32>, ParseError> {
+        let nums = source.parse(parse_start)?;
+        info!("nums: {:?}", nums);
+        Ok(nums)
+    }
+
+    fn part1(nums: &Vec<i32>) -> Option<String> {
+        Some(play_game(nums, 2020).to_string())
+    }
+
+    fn part2(nums: &Vec<i32>) -> Option<String> {
+        Some(play_game(nums, 30_000_000).to_string())
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated> {
+        Some(generate_start(rng, size))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+
+    #[test]
+    fn test_play_game() {
+        assert_eq!(play_game(&[0, 3, 6], 10), 0);
+        assert_eq!(play_game(&[0, 3, 6], 2020), 436);
+        assert_eq!(play_game(&[1, 3, 2], 2020), 1);
+    }
+}
@@ -0,0 +1,235 @@
+use crate::error::{parse_at, ParseError};
+use crate::generate::{self, Generated};
+use crate::rng::Rng;
+use crate::solution::Solution;
+use crate::util::{self, Source};
+use crate::{debug, info};
+use regex::Regex;
+use std::{collections::{HashMap, HashSet}, ops::RangeInclusive};
+
+lazy_static! {
+    static ref RULE_RE: Regex = Regex::new(r"^([^:]+): (\d+)-(\d+) or (\d+)-(\d+)$").unwrap();
+}
+
+#[derive(Debug)]
+pub struct Rule {
+    pub name: String,
+    pub r1: RangeInclusive<i32>,
+    pub r2: RangeInclusive<i32>,
+}
+
+impl Rule {
+    fn contains(&self, num: i32) -> bool {
+        self.r1.contains(&num) || self.r2.contains(&num)
+    }
+}
+
+pub fn parse_rule(rule: &str) -> Result<Rule, ParseError> {
+    let caps = RULE_RE
+        .captures(rule)
+        .ok_or_else(|| ParseError::whole(rule, "Expected a rule like 'class: 1-3 or 5-7'"))?;
+    let name = String::from(&caps[1]);
+    let low0 = parse_at::<i32>(rule, &caps[2])?;
+    let hi0 = parse_at::<i32>(rule, &caps[3])?;
+    let low1 = parse_at::<i32>(rule, &caps[4])?;
+    let hi1 = parse_at::<i32>(rule, &caps[5])?;
+
+    Ok(Rule {
+        name,
+        r1: low0..=hi0,
+        r2: low1..=hi1,
+    })
+}
+
+pub fn parse_rules(rules: &str) -> Result<Vec<Rule>, ParseError> {
+    util::parse_text_lines(rules, parse_rule)
+}
+
+pub fn parse_ticket(ticket: &str) -> Result<Vec<i32>, ParseError> {
+    ticket.split(',').map(|x| parse_at::<i32>(ticket, x)).collect()
+}
+
+/// Parse the tickets after the header line of a chunk, checking they all have one number per rule.
+fn parse_tickets(chunk: &str, header: &str, num_fields: usize) -> Result<Vec<Vec<i32>>, ParseError> {
+    let mut lines = chunk.lines();
+    let first = lines.next().unwrap_or("");
+    if first != header {
+        return Err(ParseError::whole(first, &format!("Expected '{}'", header)));
+    }
+
+    let mut tickets = vec![];
+    for (i, line) in lines.enumerate() {
+        if line.is_empty() {
+            continue;
+        }
+        let ticket = parse_ticket(line).map_err(|e| e.shift(i + 1))?;
+        if ticket.len() != num_fields {
+            let msg = format!("Expected {} fields, got {}", num_fields, ticket.len());
+            return Err(ParseError::whole(line, &msg).shift(i + 1));
+        }
+        tickets.push(ticket);
+    }
+    Ok(tickets)
+}
+
+pub fn determine_rules(rules: &[Rule], tickets: &[Vec<i32>]) -> HashMap<String, usize> {
+    let mut possible_rules: Vec<(&str, HashSet<usize>)> = vec![];
+
+    for rule in rules.iter() {
+        let poss = (0..rules.len()).filter(|&i| tickets.iter().all(|ticket| rule.contains(ticket[i]))).collect::<HashSet<_>>();
+        possible_rules.push((&rule.name, poss));
+    }
+
+    // XXX why do you have to unwrap partial_cmp()?
+    possible_rules.sort_by(|a, b| b.1.len().partial_cmp(&a.1.len()).unwrap());
+
+    debug!("possible rules: {:?}", possible_rules);
+
+    let mut result: HashMap<String, usize> = HashMap::new();
+    while let Some((name, poss)) = possible_rules.pop() {
+        if poss.len() != 1 {
+            panic!("Too many possibilities: {} {:?}", name, poss);
+        }
+        let i = poss.iter().next().unwrap();
+        result.insert(String::from(name), *i);
+
+        for (_, p) in possible_rules.iter_mut() {
+            p.remove(i);
+        }
+    }
+
+    result
+}
+
+/// The rules, your ticket and the nearby tickets.
+pub struct Notes {
+    pub rules: Vec<Rule>,
+    pub my_ticket: Vec<i32>,
+    pub nearby: Vec<Vec<i32>>,
+}
+
+pub fn parse_notes(text: &str) -> Result<Notes, ParseError> {
+    let chunks = util::paragraphs(text);
+    if chunks.len() != 3 {
+        return Err(ParseError::general(&format!(
+            "Expected rules, your ticket and nearby tickets, got {} sections",
+            chunks.len()
+        )));
+    }
+
+    let rules = parse_rules(chunks[0].1)?;
+    debug!("Rules: {:?}", rules);
+
+    let (start, chunk) = chunks[1];
+    let mut mine = parse_tickets(chunk, "your ticket:", rules.len()).map_err(|e| e.shift(start))?;
+    if mine.len() != 1 {
+        return Err(ParseError::whole(chunk.lines().next().unwrap(), "Expected one ticket").shift(start));
+    }
+    let my_ticket = mine.remove(0);
+
+    let (start, chunk) = chunks[2];
+    let nearby = parse_tickets(chunk, "nearby tickets:", rules.len()).map_err(|e| e.shift(start))?;
+
+    Ok(Notes { rules, my_ticket, nearby })
+}
+
+pub fn read_notes(path: &str) -> Result<Notes, ParseError> {
+    Source::read(path)?.parse(parse_notes)
+}
+
+pub fn departure_product(notes: &Notes) -> u64 {
+    let Notes { rules, my_ticket, nearby } = notes;
+    let mut ok_tickets: Vec<Vec<i32>> = vec![];
+    ok_tickets.push(my_ticket.clone());
+
+    for ticket in nearby {
+        let is_ok = ticket.iter().all(|&num|
+            rules.iter().any(|rule| rule.contains(num)));
+        if is_ok {
+            ok_tickets.push(ticket.clone());
+        }
+    }
+
+    debug!("OK tickets: {:?}", ok_tickets);
+
+    let rule_indices = determine_rules(rules, &ok_tickets);
+    info!("Rule indices: {:?}", rule_indices);
+
+    let mut result = 1u64;
+    for (n, &i) in rule_indices.iter() {
+        if n.starts_with("departure") {
+            info!("{}: index {} value {}", n, i, my_ticket[i]);
+            result *= my_ticket[i] as u64;
+        }
+    }
+    result
+}
+
+/// Notes with twenty rules and `size` nearby tickets, about one in five of which
+/// has a value no rule allows.
+///
+/// The `i`th rule (before shuffling) has a band of values of its own, and its first
+/// range covers the bands of every rule before it too, so it could be any of `i + 1`
+/// fields. That's the chain that `determine_rules` untangles one field at a time.
+/// The second ranges sit above all the bands and are never used.
+pub fn generate_notes(rng: &mut Rng, size: usize) -> Generated {
+    const FIELDS: usize = 20;
+    let band = |rng: &mut Rng, i: usize| rng.range(10 * i as i64 + 10..10 * i as i64 + 16) as i32;
+    let mut names: Vec<String> = ["location", "station", "platform", "track", "date", "time"]
+        .iter()
+        .map(|n| format!("departure {}", n))
+        .collect();
+    names.extend(generate::words(rng, FIELDS - names.len()));
+
+    let mut rules: Vec<String> = (0..FIELDS)
+        .map(|i| {
+            let r2 = 10 * FIELDS + 10 + 5 * i;
+            format!("{}: 10-{} or {}-{}", names[i], 10 * i + 15, r2, r2 + 2)
+        })
+        .collect();
+    rng.shuffle(&mut rules);
+
+    // The fields are in a random order on the tickets too.
+    let mut order: Vec<usize> = (0..FIELDS).collect();
+    rng.shuffle(&mut order);
+    let ticket = |rng: &mut Rng| order.iter().map(|&i| band(rng, i)).collect::<Vec<_>>();
+    let mine = ticket(rng);
+    let departures: u64 = order.iter().zip(&mine).filter(|(&i, _)| i < 6).map(|(_, &v)| v as u64).product();
+
+    let nearby: Vec<String> = (0..size)
+        .map(|_| {
+            let mut t = ticket(rng);
+            if rng.below(5) == 0 {
+                let i = rng.below(FIELDS as u64) as usize;
+                t[i] = if rng.bool() { rng.range(0..10) as i32 } else { rng.range(20 * FIELDS as i64..1000) as i32 };
+            }
+            t.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(",")
+        })
+        .collect();
+
+    let mine = mine.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(",");
+    Generated {
+        input: format!("{}\n\nyour ticket:\n{}\n\nnearby tickets:\n{}", rules.join("\n"), mine, nearby.join("\n")),
+        part2: Some(departures.to_string()),
+        ..Generated::default()
+    }
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    type Input = Notes;
+    const DAY: u32 = 16;
+
+    fn parse(source: &Source, _args: &[String]) -> Result<Notes, ParseError> {
+        source.parse(parse_notes)
+    }
+
+    fn part2(notes: &Notes) -> Option<String> {
+        Some(departure_product(notes).to_string())
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated> {
+        Some(generate_notes(rng, size))
+    }
+}
@@ -0,0 +1,134 @@
+use crate::error::ParseError;
+use crate::generate::Generated;
+use crate::grid::Grid;
+use crate::life::{Life, Rules, Square};
+use crate::rng::Rng;
+use crate::solution::{parse_arg, Solution};
+use crate::util::Source;
+use crate::info;
+
+
+pub fn parse_char(c: char) -> Option<bool> {
+    match c {
+        '.' => Some(false),
+        '#' => Some(true),
+        _ => None,
+    }
+}
+
+/// The active cubes in the initial 2D slice, and how the simulation should run.
+pub struct Pocket {
+    pub slice: Vec<(i32, i32)>,
+    /// Dimensions for part 2.
+    pub dims: usize,
+    pub cycles: usize,
+}
+
+pub fn parse_slice(text: &str) -> Result<Vec<(i32, i32)>, ParseError> {
+    let slice = Grid::parse(text, parse_char)?;
+    Ok(slice
+        .iter()
+        .filter(|&(_, &active)| active)
+        .map(|((x, y), _)| (x as i32, y as i32))
+        .collect())
+}
+
+/// Put the slice at the origin of the other dimensions.
+fn embed<const N: usize>(slice: &[(i32, i32)]) -> Vec<[i32; N]> {
+    slice
+        .iter()
+        .map(|&(x, y)| {
+            let mut cell = [0; N];
+            cell[0] = x;
+            cell[1] = y;
+            cell
+        })
+        .collect()
+}
+
+fn run_cycles_in<const N: usize>(slice: &[(i32, i32)], num_cycles: usize) -> usize {
+    let mut life = Life::new(Square::<N>::new(), Rules::conway(), embed::<N>(slice));
+    life.run(num_cycles);
+    info!("# active: {}", life.num_active());
+    life.num_active()
+}
+
+/// The number of active cubes after `num_cycles` cycles in `dims` dimensions.
+pub fn run_cycles(slice: &[(i32, i32)], dims: usize, num_cycles: usize) -> usize {
+    match dims {
+        2 => run_cycles_in::<2>(slice, num_cycles),
+        3 => run_cycles_in::<3>(slice, num_cycles),
+        4 => run_cycles_in::<4>(slice, num_cycles),
+        5 => run_cycles_in::<5>(slice, num_cycles),
+        _ => panic!("Unsupported number of dimensions: {}", dims),
+    }
+}
+
+/// A random square slice `size` cubes across, about half of them active. Anything
+/// much past 20 across makes for a slow part 2.
+pub fn generate_slice(rng: &mut Rng, size: usize) -> Generated {
+    let n = size.max(1);
+    let slice = Grid::from_fn(n, n, |_, _| rng.bool());
+    Generated::new(slice.render(|&active| if active { '#' } else { '.' }))
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    type Input = Pocket;
+    const DAY: u32 = 17;
+
+    /// Optional args are the number of dimensions for part 2 (2 to 5, default 4) and
+    /// the number of cycles (default 6).
+    fn parse(source: &Source, args: &[String]) -> Result<Pocket, ParseError> {
+        let dims = parse_arg(args, 0, 4)?;
+        if !(2..=5).contains(&dims) {
+            return Err(ParseError::arg(&args[0], "Expected 2 to 5 dimensions"));
+        }
+        Ok(Pocket {
+            slice: source.parse(parse_slice)?,
+            dims,
+            cycles: parse_arg(args, 1, 6)?,
+        })
+    }
+
+    fn part1(pocket: &Pocket) -> Option<String> {
+        Some(run_cycles(&pocket.slice, 3, pocket.cycles).to_string())
+    }
+
+    fn part2(pocket: &Pocket) -> Option<String> {
+        Some(run_cycles(&pocket.slice, pocket.dims, pocket.cycles).to_string())
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated> {
+        Some(generate_slice(rng, size))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+
+    #[test]
+    fn test_next_state() {
+        // .#.
+        // ..#
+        // ###
+        let slice = [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)];
+        let mut life = Life::new(Square::<4>::new(), Rules::conway(), embed::<4>(&slice));
+        assert_eq!(life.num_active_neighbors(&[1, 1, 0, 0]), 5);
+        life.step();
+        assert!(!life.is_active(&[1, 0, 0, 0]));
+        assert!(life.is_active(&[2, 1, 0, 0]));
+        assert!(life.is_active(&[1, 3, 0, 0]));
+        assert_eq!(life.num_active(), 29);
+    }
+
+    #[test]
+    fn test_run_cycles() {
+        let slice = [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)];
+        assert_eq!(112, run_cycles(&slice, 3, 6));
+        assert_eq!(848, run_cycles(&slice, 4, 6));
+    }
+}
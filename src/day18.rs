@@ -0,0 +1,222 @@
+use crate::error::ParseError;
+use crate::generate::Generated;
+use crate::rng::Rng;
+use crate::solution::Solution;
+use crate::util::{self, Source};
+use crate::debug;
+
+use pest::{Parser, iterators::{Pair, Pairs}, prec_climber::PrecClimber};
+use pest::prec_climber::{Assoc,Operator};
+
+#[derive(Parser)]
+#[grammar = "day18.pest"]
+struct ExprParser;
+
+/*
+enum Op {
+    MUL,
+    ADD
+}
+*/
+
+lazy_static! {
+    static ref PREC_CLIMBER: PrecClimber<Rule> = {
+        use Rule::*;
+        use Assoc::*;
+
+        PrecClimber::new(vec![
+            Operator::new(multiply, Left),
+            Operator::new(add, Left),
+        ])
+    };
+}
+
+// TODO: read a little more about how this works
+// TODO: why do I need to trim the whitespace from my digits?
+fn eval(expression: Pairs<Rule>) -> i64 {
+    PREC_CLIMBER.climb(
+        expression,
+        |pair: Pair<Rule>| match pair.as_rule() {
+            Rule::number => pair.as_str().trim().parse::<i64>().unwrap(),
+            Rule::expr => eval(pair.into_inner()),
+            _ => unreachable!(),
+        },
+        |lhs: i64, op: Pair<Rule>, rhs: i64| match op.as_rule() {
+            Rule::add      => lhs + rhs,
+            Rule::multiply => lhs * rhs,
+            _ => unreachable!(),
+        },
+    )
+}
+
+/*
+fn evaluate_expr(expr: Pair<Rule>) -> i64 {
+    let mut last_op: Option<Op> = None;
+    let mut tally = 0;
+    for term in expr.into_inner() {
+        match term.as_rule() {
+            Rule::number => {
+                let num = term.as_str().trim().parse::<i64>().expect(&format!("Failed to parse '{}'", term.as_str()));
+                match last_op {
+                    None => tally = num,
+                    Some(Op::ADD) => tally += num,
+                    Some(Op::MUL) => tally *= num,
+                }
+                last_op = None;
+            },
+            Rule::add => {
+                assert!(last_op.is_none());
+                last_op = Some(Op::ADD);
+            },
+            Rule::multiply => {
+                assert!(last_op.is_none());
+                last_op = Some(Op::MUL);
+            },
+            Rule::expr => {
+                let num = evaluate_expr(term);
+                // TODO: merge w/ number arm
+                match last_op {
+                    None => tally = num,
+                    Some(Op::ADD) => tally += num,
+                    Some(Op::MUL) => tally *= num,
+                }
+                last_op = None;
+            },
+            _ => unreachable!("Unimplemented term: {:?}", term),
+        }
+    }
+
+    tally
+}
+*/
+
+
+fn parse_expression(text: &str) -> Result<Pair<'_, Rule>, ParseError> {
+    let mut pairs = ExprParser::parse(Rule::calculation, text).map_err(|e| ParseError::from_pest(text, e))?;
+    Ok(pairs.next().unwrap()) // the `calculation` rule always produces an `expr`
+}
+
+pub fn evaluate(text: &str) -> Result<i64, ParseError> {
+    let expr = parse_expression(text)?;
+
+    // expr.into_inner();
+    debug!("expr: {:?}", expr);
+    Ok(eval(expr.into_inner()))
+}
+
+/// Read the expressions, checking that they're all well-formed.
+pub fn parse_expressions(text: &str) -> Result<Vec<String>, ParseError> {
+    util::parse_text_lines(text, |line| parse_expression(line).map(|_| String::from(line)))
+}
+
+pub fn read_expressions(path: &str) -> Result<Vec<String>, ParseError> {
+    Source::read(path)?.parse(parse_expressions)
+}
+
+/// A random expression of two to five terms, each a digit or (up to `depth` deep) a
+/// parenthesized expression, along with its value with addition first. That's
+/// easy to work out directly: it's the product of the runs of terms joined by `+`.
+/// Returns None if the value would overflow.
+fn random_expression(rng: &mut Rng, depth: u32) -> Option<(String, i64)> {
+    let mut text = String::new();
+    let mut product = 1i64;
+    let mut sum = 0i64;
+    for i in 0..rng.range(2..6) {
+        if i > 0 {
+            if rng.bool() {
+                text += " + ";
+            } else {
+                text += " * ";
+                product = product.checked_mul(sum)?;
+                sum = 0;
+            }
+        }
+        let (term, value) = if depth > 0 && rng.below(4) == 0 {
+            let (inner, value) = random_expression(rng, depth - 1)?;
+            (format!("({})", inner), value)
+        } else {
+            let digit = rng.range(1..10);
+            (digit.to_string(), digit)
+        };
+        text += &term;
+        sum = sum.checked_add(value)?;
+    }
+    Some((text, product.checked_mul(sum)?))
+}
+
+/// `size` random expressions, nested up to three deep, with the answer to part 2
+/// worked out as they're made.
+pub fn generate_expressions(rng: &mut Rng, size: usize) -> Generated {
+    let mut lines = vec![];
+    let mut tally = 0i64;
+    while lines.len() < size.max(1) {
+        if let Some((line, value)) = random_expression(rng, 3).filter(|&(_, v)| v < 1 << 40) {
+            lines.push(line);
+            tally += value;
+        }
+    }
+    Generated {
+        input: lines.join("\n"),
+        part2: Some(tally.to_string()),
+        ..Generated::default()
+    }
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    type Input = Vec<String>;
+    const DAY: u32 = 18;
+
+    fn parse(source: &Source, _args: &[String]) -> Result<Vec<String>, ParseError> {
+        source.parse(parse_expressions)
+    }
+
+    fn part2(lines: &Vec<String>) -> Option<String> {
+        let tally = lines.iter().map(|line| evaluate(line).unwrap()).sum::<i64>(); // checked in parse
+        Some(tally.to_string())
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated> {
+        Some(generate_expressions(rng, size))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+
+    #[test]
+    fn test0() {
+        assert_eq!(evaluate("1 + 2 * 3 + 4 * 5 + 6"), Ok(231));
+        assert_eq!(evaluate("1 + (2 * 3) + (4 * (5 + 6))"), Ok(51));
+    }
+
+    #[test]
+    fn test1() {
+        assert_eq!(evaluate("2 * 3 + (4 * 5)"), Ok(46));
+    }
+
+    #[test]
+    fn test2() {
+        assert_eq!(evaluate("5 + (8 * 3 + 9 + 3 * 4 * 3)"), Ok(1445));
+    }
+
+    #[test]
+    fn test3() {
+        assert_eq!(evaluate("5 * 9 * (7 * 3 * 3 + 9 * 3 + (8 + 6 * 4))"), Ok(669060));
+    }
+
+    #[test]
+    fn test4() {
+        assert_eq!(evaluate("((2 + 4 * 9) * (6 + 9 * 8 + 6) + 6) + 2 + 4 * 2"), Ok(23340));
+    }
+
+    #[test]
+    fn test_bad_expression() {
+        let err = evaluate("1 + (2 * ) + 3").unwrap_err();
+        assert_eq!(10, err.column);
+        assert!(evaluate("1 + 2 -").is_err());
+    }
+}
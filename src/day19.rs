@@ -0,0 +1,380 @@
+use crate::error::{parse_at, ParseError};
+use crate::generate::Generated;
+use crate::rng::Rng;
+use crate::solution::Solution;
+use crate::util::{self, Source};
+use crate::{debug, info};
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use itertools::Itertools;
+
+#[derive(Debug)]
+pub enum Rule {
+    Literal(char),
+    Pattern(Vec<Vec<i32>>),
+}
+
+impl Rule {
+    /// Try to match the start of txt, returning the remainder.
+    fn match_str<'a>(&self, txt: &'a str, rules: &HashMap<i32, Rule>) -> Option<&'a str> {
+        debug!("match? {} Rule {:?}", txt, self);
+        if txt.is_empty() {
+            return None;
+        }
+
+        match self {
+            Rule::Literal(c) => if txt.chars().next().unwrap_or(' ') == *c { Some(&txt[1..]) } else { None },
+            Rule::Pattern(pats) => {
+                for pat in pats {
+                    let mut rest = txt;
+                    let mut is_match = false;
+                    for id in pat {
+                        let rule = rules.get(id).unwrap();
+                        if let Some(r) = rule.match_str(rest, rules) {
+                            is_match = true;
+                            rest = r;
+                        } else {
+                            is_match = false;
+                            break;
+                        }
+                    }
+                    if is_match {
+                        debug!("match! {:?}, rest: {}", pat, rest);
+                        return Some(rest);
+                    }
+                }
+
+                None
+            }
+        }
+    }
+}
+
+lazy_static! {
+    // 3: 4 5 | 5 4
+    // 4: "a"
+    static ref LITERAL_RE: Regex = Regex::new(r#"^ *(\d+): "([a-z])"$"#).unwrap();
+    static ref PATTERN_RE: Regex = Regex::new(r#"^ *(\d+): (.*)$"#).unwrap();
+}
+
+pub fn parse_rule(rule: &str) -> Result<(i32, Rule), ParseError> {
+    if let Some(caps) = LITERAL_RE.captures(rule) {
+        let id = parse_at::<i32>(rule, &caps[1])?;
+        let c = parse_at::<char>(rule, &caps[2])?;
+        return Ok((id, Rule::Literal(c)));
+    }
+    if let Some(caps) = PATTERN_RE.captures(rule) {
+        let id = parse_at::<i32>(rule, &caps[1])?;
+        let rest = caps.get(2).unwrap().as_str();
+        let pats = rest.split(" | ")
+            .map(|pat| pat.split(' ')
+                .map(|n| parse_at::<i32>(rule, n))
+                .collect::<Result<Vec<_>, _>>())
+            .collect::<Result<Vec<_>, _>>()?;
+        return Ok((id, Rule::Pattern(pats)));
+    }
+
+    Err(ParseError::whole(rule, "Expected a rule like '0: 1 2 | 2 1' or '1: \"a\"'"))
+}
+
+/// Parse the rules, checking that every rule they refer to exists.
+pub fn parse_rules(rules: &str) -> Result<HashMap<i32, Rule>, ParseError> {
+    let parsed = util::parse_text_lines(rules, parse_rule)?;
+    let ids: HashSet<i32> = parsed.iter().map(|(id, _)| *id).collect();
+    for (i, line) in rules.lines().enumerate() {
+        let refs = line.split(':').nth(1).unwrap_or("").split_whitespace();
+        for n in refs.filter(|&n| n != "|" && !n.starts_with('"')) {
+            if !ids.contains(&parse_at::<i32>(line, n)?) {
+                return Err(ParseError::at(line, n, &format!("No rule {}", n)).shift(i));
+            }
+        }
+    }
+    Ok(parsed.into_iter().collect())
+}
+
+pub fn expand_rule(rule: &Rule, rules: &HashMap<i32, Rule>) -> HashSet<String> {
+    let mut s = HashSet::new();
+    match rule {
+        Rule::Literal(c) => { s.insert(c.to_string()); },
+        Rule::Pattern(pats) => {
+            for pat in pats {
+                let mut pieces = pat.iter().map(|i| expand_rule(&rules[i], rules)).collect::<Vec<_>>();
+                let mut poss = pieces.remove(0);
+                for piece in pieces {
+                    let x = poss.iter().cartesian_product(piece.iter());
+                    poss = x.map(|(a, b)| format!("{}{}", a, b)).collect();
+                }
+                s.extend(poss);
+            }
+        },
+    }
+    s
+}
+
+pub fn match2(text: &str, starts: &HashSet<String>, ends: &HashSet<String>) -> bool {
+    debug!("{}", text);
+    let n = starts.iter().next().unwrap().len();
+    for start in starts {
+        assert_eq!(start.len(), n);
+    }
+    for end in ends {
+        assert_eq!(end.len(), n);
+        assert!(!starts.contains(end));
+    }
+
+    let mut num42 = 0;
+    let mut rest: &str = text;
+    while n <= rest.len() && starts.contains(&rest[..n]) {
+        debug!(" 42: {}", &rest[..n]);
+        rest = &rest[n..];
+        num42 += 1;
+    }
+
+    let mut num31 = 0;
+    while n <= rest.len() && ends.contains(&rest[..n]) {
+        debug!(" 31: {}", &rest[..n]);
+        rest = &rest[n..];
+        num31 += 1;
+    }
+
+    debug!(" 42: {}, 31: {}, rest: {}", num42, num31, rest);
+    rest.is_empty() && num31 < num42 && num31 > 0 && num42 > 0
+}
+
+/// The rules and the messages to check against them.
+pub struct Puzzle {
+    pub rules: HashMap<i32, Rule>,
+    pub messages: Vec<String>,
+}
+
+pub fn parse_puzzle(text: &str) -> Result<Puzzle, ParseError> {
+    let chunks = util::paragraphs(text);
+    if chunks.len() != 2 {
+        return Err(ParseError::general(&format!(
+            "Expected rules and messages, got {} sections",
+            chunks.len()
+        )));
+    }
+
+    let rules = parse_rules(chunks[0].1)?;
+    if !rules.contains_key(&0) {
+        return Err(ParseError::general("There is no rule 0"));
+    }
+    let messages = chunks[1].1
+        .split('\n')
+        .filter(|line| !line.is_empty())
+        .map(String::from)
+        .collect();
+    Ok(Puzzle { rules, messages })
+}
+
+pub fn read_puzzle(path: &str) -> Result<Puzzle, ParseError> {
+    Source::read(path)?.parse(parse_puzzle)
+}
+
+pub fn count_matches(puzzle: &Puzzle) -> usize {
+    let rules = &puzzle.rules;
+    let rule0 = &rules[&0];
+    puzzle
+        .messages
+        .iter()
+        .filter(|line| rule0.match_str(line, rules) == Some(""))
+        .count()
+}
+
+/// Part 2: rule 0 becomes some number of 42s followed by a smaller number of 31s.
+/// Returns None if there are no rules 42 and 31 (e.g. for the first sample).
+pub fn count_looped_matches(puzzle: &Puzzle) -> Option<usize> {
+    let rules = &puzzle.rules;
+    let rule42s = expand_rule(rules.get(&42)?, rules);
+    let rule31s = expand_rule(rules.get(&31)?, rules);
+    debug!("42s ({}): {:?}", rule42s.len(), rule42s);
+    debug!("31s ({}): {:?}", rule31s.len(), rule31s);
+    info!("len: {}", rule31s.iter().next().unwrap().len());
+
+    let num_ok = puzzle
+        .messages
+        .iter()
+        .filter(|line| match2(line, &rule42s, &rule31s))
+        .count();
+    Some(num_ok)
+}
+
+/// Rules shaped like the real ones, and `size` messages, with both answers planted.
+///
+/// Rule 42 is an `a` and rule 31 a `b`, each followed by a few letters of either
+/// kind, so every block has the same length and the two never overlap. A message
+/// is some 42 blocks and then some 31 blocks, or is spoiled by leading with a 31
+/// block or by an extra letter at the end.
+pub fn generate_puzzle(rng: &mut Rng, size: usize) -> Generated {
+    // Every rule gets a fresh number, apart from the ones the puzzle fixes.
+    let mut ids: Vec<i32> = (1..200).filter(|id| ![8, 11, 31, 42].contains(id)).collect();
+    rng.shuffle(&mut ids);
+    let (a, b, letter, tail) = (ids[0], ids[1], ids[2], ids[3]);
+    let len = rng.range(3..6) as usize;
+    let mut rules = vec![
+        String::from("0: 8 11"),
+        String::from("8: 42"),
+        String::from("11: 42 31"),
+        format!("42: {} {}", a, tail),
+        format!("31: {} {}", b, tail),
+        format!("{}: \"a\"", a),
+        format!("{}: \"b\"", b),
+        format!("{}: {} | {}", letter, a, b),
+        format!("{}: {}", tail, vec![letter.to_string(); len].join(" ")),
+    ];
+    rng.shuffle(&mut rules);
+
+    let block = |rng: &mut Rng, first: char| {
+        let rest: String = (0..len).map(|_| if rng.bool() { 'a' } else { 'b' }).collect();
+        format!("{}{}", first, rest)
+    };
+    let mut messages = vec![];
+    let (mut part1, mut part2) = (0, 0);
+    for _ in 0..size {
+        let num42 = rng.range(1..5);
+        let num31 = rng.range(0..4);
+        let mut message: String = (0..num42).map(|_| block(rng, 'a')).collect::<String>()
+            + &(0..num31).map(|_| block(rng, 'b')).collect::<String>();
+        match rng.below(5) {
+            0 => message.insert_str(0, &block(rng, 'b')),
+            1 => message.push(if rng.bool() { 'a' } else { 'b' }),
+            _ => {
+                part1 += (num42 == 2 && num31 == 1) as usize;
+                part2 += (num42 > num31 && num31 > 0) as usize;
+            }
+        }
+        messages.push(message);
+    }
+    Generated {
+        input: format!("{}\n\n{}", rules.join("\n"), messages.join("\n")),
+        part1: Some(part1.to_string()),
+        part2: Some(part2.to_string()),
+        ..Generated::default()
+    }
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    type Input = Puzzle;
+    const DAY: u32 = 19;
+
+    fn parse(source: &Source, _args: &[String]) -> Result<Puzzle, ParseError> {
+        source.parse(parse_puzzle)
+    }
+
+    fn part1(puzzle: &Puzzle) -> Option<String> {
+        Some(count_matches(puzzle).to_string())
+    }
+
+    fn part2(puzzle: &Puzzle) -> Option<String> {
+        count_looped_matches(puzzle).map(|n| n.to_string())
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated> {
+        Some(generate_puzzle(rng, size))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+
+    #[test]
+    fn test0() {
+        let rules = parse_rules(r#"0: 1 2
+        1: "a"
+        2: 1 3 | 3 1
+        3: "b""#).unwrap();
+        let rule0 = &rules[&0];
+        // aab or aba
+        assert_eq!(Some(""), rule0.match_str("aab", &rules));
+        assert_eq!(Some(""), rule0.match_str("aba", &rules));
+        assert_eq!(None, rule0.match_str("bab", &rules));
+    }
+
+
+    #[test]
+    fn test1() {
+        let rules = parse_rules(r#"0: 4 1 5
+        1: 2 3 | 3 2
+        2: 4 4 | 5 5
+        3: 4 5 | 5 4
+        4: "a"
+        5: "b""#).unwrap();
+        let rule0 = &rules[&0];
+        // ababbb and abbbab match, but
+        // bababa, aaabbb, and aaaabbb do not
+        assert_eq!(Some(""), rule0.match_str("ababbb", &rules));
+        assert_eq!(Some(""), rule0.match_str("abbbab", &rules));
+        assert_eq!(None, rule0.match_str("bababa", &rules));
+        assert_eq!(None, rule0.match_str("aaabbb", &rules));
+        // match_str only needs to match a prefix
+        assert_eq!(Some("b"), rule0.match_str("aaaabbb", &rules));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = parse_rule("1: 2 x").unwrap_err();
+        assert_eq!((6, 1), (err.column, err.len));
+
+        let err = parse_rules("0: 1 2\n1: \"a\"").unwrap_err();
+        assert_eq!((1, 6), (err.line, err.column));
+        assert_eq!("No rule 2", err.message);
+    }
+
+    use std::iter::FromIterator;
+
+    fn hashset(strs: &[&str]) -> HashSet<String> {
+        strs.iter().map(|&x| String::from(x)).collect()
+    }
+
+    macro_rules! set(
+        { $($key:expr),+ } => {
+            {
+                let mut m = ::std::collections::HashSet::new();
+                $(
+                    m.insert(String::from($key));
+                )+
+                m
+            }
+         };
+    );
+
+    #[test]
+    fn test_expand() {
+        let rules = parse_rules(r#"0: 4 1 5
+        1: 2 3 | 3 2
+        2: 4 4 | 5 5
+        3: 4 5 | 5 4
+        4: "a"
+        5: "b""#).unwrap();
+        // TODO: find a better way to write HashSet<String> literals
+        assert_eq!(expand_rule(&rules[&4], &rules), HashSet::from_iter(vec![String::from("a")]));
+        assert_eq!(
+            expand_rule(&rules[&3], &rules),
+            set!("ab", "ba"),
+            // hashset(&["ab", "ba"]),
+        );
+        assert_eq!(
+            expand_rule(&rules[&2], &rules),
+            hashset(&["aa", "bb"])
+        );
+        assert_eq!(
+            expand_rule(&rules[&1], &rules),
+            hashset(&[
+                "aaab",
+                "aaba",
+                "bbab",
+                "bbba",
+                "abaa",
+                "abbb",
+                "baaa",
+                "babb",
+            ])
+        );
+    }
+}
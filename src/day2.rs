@@ -0,0 +1,496 @@
+use pest::iterators::{Pair, Pairs};
+use pest::prec_climber::{Assoc, Operator, PrecClimber};
+use pest::Parser;
+use regex::Regex;
+use std::fmt;
+
+use crate::error::{parse_at, ParseError};
+use crate::generate::Generated;
+use crate::rng::Rng;
+use crate::solution::{check_flags, flag_values, Solution};
+use crate::util::{self, Source};
+use crate::debug;
+
+pub struct Password {
+  pub password: String,
+  pub policy_char: char,
+  pub min: u32,
+  pub max: u32
+}
+
+pub fn read_password(line: &str) -> Result<Password, ParseError> {
+  lazy_static! {
+    static ref RE: Regex = Regex::new(r"^(\d+)-(\d+) ([a-z]): ([a-z]+)$").unwrap();
+  }
+  // vscode isn't able to follow along with the types for RE
+  // let re: Regex = Regex::new("^(\\d+)-(\\d+): ([a-z]+)$").unwrap();
+
+  let groups = RE
+    .captures(line)
+    .ok_or_else(|| ParseError::whole(line, "Expected a password like '1-3 a: abcde'"))?;
+  let min = parse_at::<u32>(line, &groups[1])?;
+  let max = parse_at::<u32>(line, &groups[2])?;
+  let policy_char = parse_at::<char>(line, &groups[3])?;
+  let password = String::from(&groups[4]);
+  Ok(Password{password, policy_char, min, max})
+}
+
+impl fmt::Display for Password {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{}-{} {}: {}", self.min, self.max, self.policy_char, self.password)
+  }
+}
+
+/// A rule that passwords have to follow. `check` says why a password breaks it.
+pub trait PasswordPolicy: Send + Sync {
+  fn name(&self) -> &str;
+  fn check(&self, pass: &Password) -> Result<(), String>;
+}
+
+fn count_letter(pass: &Password) -> u32 {
+  pass.password.chars().filter(|&c| c == pass.policy_char).count() as u32
+}
+
+/// Whether the letter is at 1-based `position`. It's never at a position outside the
+/// password.
+fn letter_at(pass: &Password, position: u32) -> bool {
+  position > 0 && pass.password.chars().nth(position as usize - 1) == Some(pass.policy_char)
+}
+
+/// Part 1's policy: the letter appears from `min` to `max` times. It's the same as
+/// `count in a..b`.
+pub struct CountRange;
+
+impl PasswordPolicy for CountRange {
+  fn name(&self) -> &str {
+    "count"
+  }
+
+  fn check(&self, pass: &Password) -> Result<(), String> {
+    let count = count_letter(pass);
+    if (pass.min..=pass.max).contains(&count) {
+      return Ok(());
+    }
+    let times = if count == 1 { "time" } else { "times" };
+    Err(format!("'{}' appears {} {}, not {} to {}", pass.policy_char, count, times, pass.min, pass.max))
+  }
+}
+
+/// Part 2's policy: the letter is at exactly one of positions `min` and `max`. It's the
+/// same as `at(a) ^ at(b)`.
+pub struct OnePosition;
+
+impl PasswordPolicy for OnePosition {
+  fn name(&self) -> &str {
+    "position"
+  }
+
+  fn check(&self, pass: &Password) -> Result<(), String> {
+    let Password { min, max, policy_char, .. } = pass;
+    match (letter_at(pass, *min), letter_at(pass, *max)) {
+      (true, false) | (false, true) => Ok(()),
+      (true, true) => Err(format!("'{}' is at both positions {} and {}", policy_char, min, max)),
+      (false, false) => Err(format!("'{}' is at neither position {} nor {}", policy_char, min, max)),
+    }
+  }
+}
+
+#[derive(Parser)]
+#[grammar = "day2.pest"]
+struct PolicyParser;
+
+lazy_static! {
+  static ref PREC_CLIMBER: PrecClimber<Rule> = {
+    use Rule::*;
+    use Assoc::*;
+
+    PrecClimber::new(vec![
+      Operator::new(or, Left),
+      Operator::new(xor, Left),
+      Operator::new(and, Left),
+    ])
+  };
+}
+
+/// A number in a policy, worked out for each password.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Value {
+  Number(u32),
+  Count,
+  Len,
+  A,
+  B,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Cmp {
+  Eq,
+  Ne,
+  Le,
+  Ge,
+  Lt,
+  Gt,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Logic {
+  And,
+  Xor,
+  Or,
+}
+
+/// A policy written in the little language in day2.pest, e.g. `count in a..b`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Condition {
+  At(Value),
+  Compare(Value, Cmp, Value),
+  /// The first value is from the second to the third, inclusive.
+  In(Value, Value, Value),
+  Not(Box<Condition>),
+  Logic(Box<Condition>, Logic, Box<Condition>),
+}
+
+impl Value {
+  fn eval(self, pass: &Password) -> u32 {
+    match self {
+      Value::Number(n) => n,
+      Value::Count => count_letter(pass),
+      Value::Len => pass.password.chars().count() as u32,
+      Value::A => pass.min,
+      Value::B => pass.max,
+    }
+  }
+}
+
+impl Condition {
+  pub fn eval(&self, pass: &Password) -> bool {
+    match self {
+      Condition::At(v) => letter_at(pass, v.eval(pass)),
+      Condition::Compare(l, cmp, r) => {
+        let (l, r) = (l.eval(pass), r.eval(pass));
+        match cmp {
+          Cmp::Eq => l == r,
+          Cmp::Ne => l != r,
+          Cmp::Le => l <= r,
+          Cmp::Ge => l >= r,
+          Cmp::Lt => l < r,
+          Cmp::Gt => l > r,
+        }
+      }
+      Condition::In(v, lo, hi) => (lo.eval(pass)..=hi.eval(pass)).contains(&v.eval(pass)),
+      Condition::Not(c) => !c.eval(pass),
+      Condition::Logic(l, Logic::And, r) => l.eval(pass) && r.eval(pass),
+      Condition::Logic(l, Logic::Xor, r) => l.eval(pass) != r.eval(pass),
+      Condition::Logic(l, Logic::Or, r) => l.eval(pass) || r.eval(pass),
+    }
+  }
+}
+
+fn build_value(text: &str, pair: Pair<Rule>) -> Result<Value, ParseError> {
+  Ok(match pair.as_rule() {
+    Rule::number => Value::Number(parse_at(text, pair.as_str())?),
+    Rule::count => Value::Count,
+    Rule::len => Value::Len,
+    Rule::a => Value::A,
+    Rule::b => Value::B,
+    _ => unreachable!(),
+  })
+}
+
+fn build_condition(text: &str, pair: Pair<Rule>) -> Result<Condition, ParseError> {
+  let rule = pair.as_rule();
+  let mut inner = pair.into_inner();
+  let value = |inner: &mut Pairs<Rule>| build_value(text, inner.next().unwrap());
+  Ok(match rule {
+    Rule::expr => build_expr(text, inner)?,
+    Rule::not => Condition::Not(Box::new(build_condition(text, inner.next().unwrap())?)),
+    Rule::at => Condition::At(value(&mut inner.next().unwrap().into_inner())?),
+    Rule::range => Condition::In(value(&mut inner)?, value(&mut inner)?, value(&mut inner)?),
+    Rule::comparison => {
+      let l = value(&mut inner)?;
+      let cmp = match inner.next().unwrap().as_str() {
+        "==" => Cmp::Eq,
+        "!=" => Cmp::Ne,
+        "<=" => Cmp::Le,
+        ">=" => Cmp::Ge,
+        "<" => Cmp::Lt,
+        _ => Cmp::Gt,
+      };
+      Condition::Compare(l, cmp, value(&mut inner)?)
+    }
+    _ => unreachable!(),
+  })
+}
+
+fn build_expr(text: &str, pairs: Pairs<Rule>) -> Result<Condition, ParseError> {
+  PREC_CLIMBER.climb(
+    pairs,
+    |pair: Pair<Rule>| build_condition(text, pair),
+    |lhs, op: Pair<Rule>, rhs| {
+      let logic = match op.as_rule() {
+        Rule::and => Logic::And,
+        Rule::xor => Logic::Xor,
+        Rule::or => Logic::Or,
+        _ => unreachable!(),
+      };
+      Ok(Condition::Logic(Box::new(lhs?), logic, Box::new(rhs?)))
+    },
+  )
+}
+
+/// A policy from the little language, e.g. `count >= 1 & !at(len)`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DslPolicy {
+  text: String,
+  condition: Condition,
+}
+
+impl DslPolicy {
+  pub fn parse(text: &str) -> Result<DslPolicy, ParseError> {
+    let mut pairs = PolicyParser::parse(Rule::policy, text).map_err(|e| ParseError::from_pest(text, e))?;
+    let condition = build_expr(text, pairs.next().unwrap().into_inner())?; // `policy` always has an `expr`
+    debug!("{}: {:?}", text, condition);
+    Ok(DslPolicy { text: String::from(text), condition })
+  }
+}
+
+impl PasswordPolicy for DslPolicy {
+  fn name(&self) -> &str {
+    &self.text
+  }
+
+  fn check(&self, pass: &Password) -> Result<(), String> {
+    if self.condition.eval(pass) {
+      return Ok(());
+    }
+    let [count, len] = [Value::Count, Value::Len].map(|v| v.eval(pass));
+    Err(format!("fails with count {}, len {}, a {}, b {}", count, len, pass.min, pass.max))
+  }
+}
+
+/// A built-in policy by name (`count` or `position`), or else one in the little language.
+pub fn parse_policy(text: &str) -> Result<Box<dyn PasswordPolicy>, ParseError> {
+  Ok(match text {
+    "count" => Box::new(CountRange),
+    "position" => Box::new(OnePosition),
+    _ => Box::new(DslPolicy::parse(text).map_err(|e| e.in_file("<args>"))?),
+  })
+}
+
+/// How many passwords follow one policy, and why each of the others doesn't.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PolicyReport {
+  pub policy: String,
+  pub valid: usize,
+  /// The index of each invalid password and what's wrong with it.
+  pub failures: Vec<(usize, String)>,
+}
+
+/// Check every password against every policy, in one pass over the passwords.
+pub fn check_passwords(passwords: &[Password], policies: &[Box<dyn PasswordPolicy>]) -> Vec<PolicyReport> {
+  let mut reports: Vec<PolicyReport> = policies
+    .iter()
+    .map(|p| PolicyReport { policy: String::from(p.name()), valid: 0, failures: vec![] })
+    .collect();
+  for (i, pass) in passwords.iter().enumerate() {
+    for (policy, report) in policies.iter().zip(reports.iter_mut()) {
+      match policy.check(pass) {
+        Ok(()) => report.valid += 1,
+        Err(reason) => report.failures.push((i, reason)),
+      }
+    }
+  }
+  reports
+}
+
+/// A summary of each report, saying which part it answers, with its first few
+/// failures. The rest of the failures are logged with -vv.
+fn report_lines(passwords: &[Password], reports: &[PolicyReport]) -> Vec<String> {
+  const SHOWN: usize = 3;
+  let mut lines = vec![];
+  for (i, report) in reports.iter().enumerate() {
+    let part = match i {
+      0 => " (part 1)",
+      1 => " (part 2)",
+      _ => "",
+    };
+    lines.push(format!("{}{}: {} valid, {} invalid passwords", report.policy, part, report.valid, report.failures.len()));
+    for (j, reason) in report.failures.iter().take(SHOWN) {
+      lines.push(format!("  {}: {}", passwords[*j], reason));
+    }
+    if report.failures.len() > SHOWN {
+      lines.push(format!("  and {} more", report.failures.len() - SHOWN));
+    }
+    for (j, reason) in report.failures.iter().skip(SHOWN) {
+      debug!("{}: {} ({})", passwords[*j], reason, report.policy);
+    }
+  }
+  lines
+}
+
+pub fn parse_passwords(text: &str) -> Result<Vec<Password>, ParseError> {
+  util::parse_text_lines(text, read_password)
+}
+
+pub fn read_passwords(path: &str) -> Result<Vec<Password>, ParseError> {
+  Source::read(path)?.parse(parse_passwords)
+}
+
+/// `size` random passwords, each of which is decided to be valid or not up front.
+/// They're mostly made of a few letters, so that the policy letter turns up a lot.
+pub fn generate_passwords(rng: &mut Rng, size: usize) -> Generated {
+  let mut lines = vec![];
+  let mut num_valid = 0;
+  for _ in 0..size {
+    let len = rng.range(2..21) as usize;
+    let c = (b'a' + rng.below(5) as u8) as char;
+    let mut password: Vec<char> = (0..len)
+      .map(|_| loop {
+        let other = (b'a' + rng.below(7) as u8) as char;
+        if other != c {
+          break other;
+        }
+      })
+      .collect();
+    let min = rng.range(1..len as i64) as usize;
+    let max = rng.range(min as i64 + 1..len as i64 + 1) as usize;
+    for (i, p) in password.iter_mut().enumerate() {
+      if i + 1 != min && i + 1 != max && rng.bool() {
+        *p = c;
+      }
+    }
+    // Valid passwords have the letter in exactly one of the two positions.
+    let valid = rng.bool();
+    let first = rng.bool();
+    let second = if valid { !first } else { first };
+    if first {
+      password[min - 1] = c;
+    }
+    if second {
+      password[max - 1] = c;
+    }
+    num_valid += valid as usize;
+    lines.push(format!("{}-{} {}: {}", min, max, c, password.into_iter().collect::<String>()));
+  }
+  Generated {
+    input: lines.join("\n"),
+    part2: Some(num_valid.to_string()),
+    ..Generated::default()
+  }
+}
+
+pub struct Passwords {
+  pub passwords: Vec<Password>,
+  pub policies: Vec<Box<dyn PasswordPolicy>>,
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+  type Input = Passwords;
+  const DAY: u32 = 2;
+
+  /// Optional args are `--policy P` flags, which replace the usual `count` (part 1) and
+  /// `position` (part 2) policies. Part 1 answers for the first policy and part 2 for
+  /// the second, if there is one. How many passwords follow each policy is shown with
+  /// the answers.
+  fn parse(source: &Source, args: &[String]) -> Result<Passwords, ParseError> {
+    check_flags(args, &["--policy"])?;
+    let mut names = flag_values(args, "--policy")?;
+    if names.is_empty() {
+      names = vec!["count", "position"];
+    }
+    let policies = names.into_iter().map(parse_policy).collect::<Result<_, _>>()?;
+    Ok(Passwords { passwords: source.parse(parse_passwords)?, policies })
+  }
+
+  fn extras(input: &Passwords) -> Result<Vec<String>, String> {
+    let reports = check_passwords(&input.passwords, &input.policies);
+    Ok(report_lines(&input.passwords, &reports))
+  }
+
+  fn part1(input: &Passwords) -> Option<String> {
+    let reports = check_passwords(&input.passwords, input.policies.get(0..1)?);
+    Some(reports[0].valid.to_string())
+  }
+
+  fn part2(input: &Passwords) -> Option<String> {
+    let reports = check_passwords(&input.passwords, input.policies.get(1..2)?);
+    Some(reports[0].valid.to_string())
+  }
+
+  fn generate(rng: &mut Rng, size: usize) -> Option<Generated> {
+    Some(generate_passwords(rng, size))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  // Note this useful idiom: importing names from outer (for mod tests) scope.
+  use super::*;
+
+  #[test]
+  fn test_policies() {
+    let passwords = parse_passwords("1-3 a: abcde\n1-3 b: cdefg\n2-9 c: ccccccccc").unwrap();
+    let policies: Vec<Box<dyn PasswordPolicy>> = ["count", "position", "count in a..b", "at(a) ^ at(b)"]
+      .iter()
+      .map(|p| parse_policy(p).unwrap())
+      .collect();
+    let reports = check_passwords(&passwords, &policies);
+    assert_eq!(vec![2, 1, 2, 1], reports.iter().map(|r| r.valid).collect::<Vec<_>>());
+    assert_eq!(vec![(1, String::from("'b' appears 0 times, not 1 to 3"))], reports[0].failures);
+    assert_eq!(
+      vec![(1, String::from("'b' is at neither position 1 nor 3")), (2, String::from("'c' is at both positions 2 and 9"))],
+      reports[1].failures
+    );
+    assert_eq!(vec![(1, String::from("fails with count 0, len 5, a 1, b 3"))], reports[2].failures);
+
+    // The built-in policies and their equivalents agree on plenty of passwords.
+    let passwords = parse_passwords(&generate_passwords(&mut Rng::new(0), 500).input).unwrap();
+    let reports = check_passwords(&passwords, &policies);
+    assert_eq!(reports[0].valid, reports[2].valid);
+    assert_eq!(reports[1].valid, reports[3].valid);
+
+    // Positions outside the password are fine, and count as not having the letter.
+    let passwords = parse_passwords("1-9 a: abc\n0-2 b: abc").unwrap();
+    let reports = check_passwords(&passwords, &policies[..2]);
+    assert_eq!(vec![2, 2], reports.iter().map(|r| r.valid).collect::<Vec<_>>());
+  }
+
+  #[test]
+  fn test_extras() {
+    let source = Source::new("test", "1-3 a: abcde\n1-3 b: cdefg\n2-9 c: ccccccccc");
+    let args: Vec<String> = ["--policy", "count", "--policy", "len > 5", "--policy", "at(1)"].iter().map(|s| s.to_string()).collect();
+    let input = Solver::parse(&source, &args).unwrap();
+    assert_eq!(Some(String::from("2")), Solver::part1(&input));
+    assert_eq!(Some(String::from("1")), Solver::part2(&input));
+    let lines = Solver::extras(&input).unwrap();
+    assert_eq!("count (part 1): 2 valid, 1 invalid passwords", lines[0]);
+    assert_eq!("len > 5 (part 2): 1 valid, 2 invalid passwords", lines[2]);
+    assert_eq!("at(1): 2 valid, 1 invalid passwords", lines[5]);
+
+    let args: Vec<String> = ["--policy", "count"].iter().map(|s| s.to_string()).collect();
+    assert_eq!(None, Solver::part2(&Solver::parse(&source, &args).unwrap()));
+  }
+
+  #[test]
+  fn test_dsl() {
+    // & binds tighter than ^, which binds tighter than |.
+    let c = DslPolicy::parse("at(1) | at(2) ^ !(len >= 3) & count != 0").unwrap().condition;
+    let at = |n| Box::new(Condition::At(Value::Number(n)));
+    let not_len = Box::new(Condition::Not(Box::new(Condition::Compare(Value::Len, Cmp::Ge, Value::Number(3)))));
+    let count = Box::new(Condition::Compare(Value::Count, Cmp::Ne, Value::Number(0)));
+    let and = Box::new(Condition::Logic(not_len, Logic::And, count));
+    let xor = Box::new(Condition::Logic(at(2), Logic::Xor, and));
+    assert_eq!(Condition::Logic(at(1), Logic::Or, xor), c);
+
+    let err = DslPolicy::parse("count in a..").unwrap_err();
+    assert_eq!(13, err.column);
+    // A bad position is the error, not the comparison it might have been part of.
+    let err = DslPolicy::parse("at(a").unwrap_err();
+    assert_eq!((4, "Expected position"), (err.column, err.message.as_str()));
+    assert!(parse_policy("at(99999999999)").is_err());
+    let pass = read_password("1-3 a: abcde").unwrap();
+    assert!(!DslPolicy::parse("at(0) | at(6)").unwrap().condition.eval(&pass));
+  }
+}
@@ -0,0 +1,814 @@
+use crate::error::{parse_at, ParseError};
+use crate::generate::Generated;
+use crate::grid::Grid;
+use crate::pool;
+use crate::rng::Rng;
+use crate::solution::Solution;
+use crate::util::{self, Source};
+use crate::{debug, info, map, set};
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use itertools::Itertools;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tile {
+    pub id: u64,
+    pub px: Grid<bool>,
+    // bitmasks, left and top = larger bits
+    pub top: u32,
+    pub left: u32,
+    pub bottom: u32,
+    pub right: u32,
+}
+
+// Tile 3079:
+// #.#.#####.
+
+lazy_static! {
+    static ref TILE_RE: Regex = Regex::new(r#"^ *Tile (\d+):$"#).unwrap();
+}
+
+pub fn to_mask(bits: &[bool]) -> u32 {
+    bits.iter().fold(0, |acc, x| 2 * acc + if *x { 1 } else { 0 })
+}
+
+pub fn flip_bits(bits: u32, n: u32) -> u32 {
+    (0..n).map(|i| ((bits & (1 << i)) >> i) << (n - 1 - i)).sum()
+}
+
+/// Parse the pixels of a tile, which must form a square of '.' and '#'.
+pub fn parse_pixels(text: &str) -> Result<Grid<bool>, ParseError> {
+    let px = Grid::parse(text, |c| match c {
+        '.' => Some(false),
+        '#' => Some(true),
+        _ => None,
+    })?;
+    if px.height() < 2 {
+        return Err(ParseError::general("Expected at least two rows of pixels"));
+    }
+    if px.width() != px.height() {
+        let line = text.lines().next().unwrap_or("");
+        let msg = format!("Expected {} pixels, got {}", px.height(), px.width());
+        return Err(ParseError::at(line, line.trim(), &msg));
+    }
+    Ok(px)
+}
+
+pub fn parse_tile(tile: &str) -> Result<Tile, ParseError> {
+    let (title, pixels) = tile.split_once('\n').unwrap_or((tile, ""));
+    let tile_cap = TILE_RE
+        .captures(title)
+        .ok_or_else(|| ParseError::whole(title, "Expected a title like 'Tile 123:'"))?;
+    let id: u64 = parse_at(title, &tile_cap[1])?;
+
+    let px = parse_pixels(pixels).map_err(|e| e.shift(1))?;
+    let top = to_mask(px.row(0));
+    let bottom = to_mask(px.row(px.height() - 1));
+    let left = to_mask(&px.column(0).copied().collect::<Vec<_>>());
+    let right = to_mask(&px.column(px.width() - 1).copied().collect::<Vec<_>>());
+
+    Ok(Tile {
+        id,
+        px,
+        left,
+        right,
+        top,
+        bottom,
+    })
+}
+
+fn masks(tile: &Tile) -> HashSet<u32> {
+    set!{tile.left, tile.right, tile.top, tile.bottom}
+}
+
+fn flipped_masks(tile: &Tile) -> HashSet<u32> {
+    let n = tile.px.width() as u32;
+    set!{
+        flip_bits(tile.left, n),
+        flip_bits(tile.right, n),
+        flip_bits(tile.top, n),
+        flip_bits(tile.bottom, n)
+    }
+}
+
+pub fn possible_masks(tile: &Tile) -> HashSet<u32> {
+    masks(tile).union(&flipped_masks(tile)).copied().collect()
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Op {
+    Identity,
+    FlipVert,
+    FlipHoriz,
+    Rot90,
+    Rot180,
+    Rot270,
+    FlipDiagTLBR,
+    FlipDiagBLTR
+}
+
+pub const OPS: [Op; 8] = [
+    Op::Identity,
+    Op::FlipVert,
+    Op::FlipHoriz,
+    Op::Rot90,
+    Op::Rot180,
+    Op::Rot270,
+    Op::FlipDiagTLBR,
+    Op::FlipDiagBLTR
+];
+
+pub fn transform_px(px: &Grid<bool>, op: Op) -> Grid<bool> {
+    match op {
+        Op::Identity => px.clone(),
+        Op::FlipVert => px.flip_vert(),
+        Op::FlipHoriz => px.flip_horiz(),
+        Op::Rot90 => px.rot90(),
+        Op::Rot180 => px.rot180(),
+        Op::Rot270 => px.rot270(),
+        Op::FlipDiagTLBR => px.transpose(),
+        Op::FlipDiagBLTR => px.anti_transpose(),
+    }
+}
+
+//                   #
+// #    ##    ##    ###
+//  #  #  #  #  #  #
+const DRAGON: [(i32, i32); 15] = [
+    (18, 0),
+    (0, 1), (5, 1), (6, 1), (11, 1), (12, 1), (17, 1), (18, 1), (19, 1),
+    (1, 2), (4, 2), (7, 2), (10, 2), (13, 2), (16, 2)
+];
+
+/// Returns the number of non-dragon cells, or None if there are no dragons.
+pub fn find_dragons(px: &Grid<bool>) -> Option<usize> {
+    let drag = DRAGON;
+
+    let g = px.iter().filter_map(
+        |((x, y), v)| if *v { Some((x as i32, y as i32)) } else { None }
+    ).collect::<HashSet<_>>();
+
+    let mut num_drag = 0;
+    let mut in_drag = HashSet::new();
+    let n = px.height() as i32;
+    for y in 0..n {
+        for x in 0..n {
+            if drag.iter().all(|(dx, dy)| g.contains(&(x + dx, y + dy))) {
+                num_drag += 1;
+                for (dx, dy) in drag.iter() {
+                    in_drag.insert((x + dx, y + dy));
+                }
+            }
+        }
+    }
+
+    if num_drag > 0 {
+        Some(g.len() - in_drag.len())
+    } else {
+        None
+    }
+}
+
+// TODO: make this call transform_px
+pub fn transform_tile(tile: &Tile, op: Op) -> Tile {
+    let n = tile.px.width() as u32;
+    let Tile {id, left, right, top, bottom, px: _} = *tile;
+    match op {
+        Op::Identity => tile.clone(),
+        Op::FlipVert => Tile {
+            id,
+            top: bottom,
+            bottom: top,
+            left: flip_bits(left, n),
+            right: flip_bits(right, n),
+            px: transform_px(&tile.px, op),
+        },
+        Op::FlipHoriz => Tile {
+            id,
+            top: flip_bits(top, n),
+            bottom: flip_bits(bottom, n),
+            left: right,
+            right: left,
+            px: transform_px(&tile.px, op),
+        },
+        Op::Rot90 => Tile {
+            id,
+            top: flip_bits(left, n),
+            right: top,
+            bottom: flip_bits(right, n),
+            left: bottom,
+            px: transform_px(&tile.px, op),
+        },
+        Op::Rot180 => transform_tile(&transform_tile(tile, Op::Rot90), Op::Rot90),
+        // TODO: I think this is a flip + rotate?
+        Op::Rot270 => transform_tile(&transform_tile(&transform_tile(tile, Op::Rot90), Op::Rot90), Op::Rot90),
+        // TODO: These could be implemented more efficiently
+        Op::FlipDiagTLBR => transform_tile(&transform_tile(tile, Op::Rot90), Op::FlipHoriz),
+        Op::FlipDiagBLTR => transform_tile(&transform_tile(tile, Op::Rot90), Op::FlipVert),
+    }
+}
+
+fn index_tiles(tiles: &[Tile]) -> HashMap<u32, Vec<&Tile>> {
+    let mut out: HashMap<u32, Vec<&Tile>> = HashMap::new();
+    for tile in tiles.iter() {
+        for mask in possible_masks(tile).iter() {
+            (*out.entry(*mask).or_default()).push(tile);
+        }
+    }
+    out
+}
+
+fn possible_neighbors<'a>(tile: &Tile, index: &'a HashMap<u32, Vec<&Tile>>) -> Vec<&'a Tile> {
+    let mut out: Vec<&Tile> = vec![];
+    let mut ids = set!{tile.id};
+    for mask in masks(tile) {
+        for other in index.get(&mask).unwrap_or(&vec![]) {
+            if !ids.contains(&other.id) {
+                out.push(other);
+                ids.insert(other.id);
+            }
+        }
+    }
+    out
+}
+
+pub fn add_to_right(left: &Tile, right: &Tile) -> Option<Op> {
+    let n = left.px.width() as u32;
+    let mask = left.right;
+    let mask_flip = flip_bits(mask, n);
+    if right.left == mask {
+        return Some(Op::Identity);
+    } else if right.left == mask_flip {
+        return Some(Op::FlipVert);
+    } else if right.bottom == mask {
+        return Some(Op::Rot90);
+    } else if right.bottom == mask_flip {
+        return Some(Op::FlipDiagBLTR);
+    } else if right.right == mask {
+        return Some(Op::FlipHoriz);
+    } else if right.right == mask_flip {
+        return Some(Op::Rot180);
+    } else if right.top == mask {
+        return Some(Op::FlipDiagTLBR);
+    } else if right.top == mask_flip {
+        return Some(Op::Rot270);
+    }
+
+    None
+}
+
+pub fn add_to_bottom(top: &Tile, bottom: &Tile) -> Option<Op> {
+    let n = top.px.width() as u32;
+    let mask = top.bottom;
+    let mask_flip = flip_bits(mask, n);
+    if bottom.top == mask {
+        return Some(Op::Identity);
+    } else if bottom.top == mask_flip {
+        return Some(Op::FlipHoriz);
+    } else if bottom.left == mask {
+        return Some(Op::FlipDiagTLBR);
+    } else if bottom.left == mask_flip {
+        return Some(Op::Rot90);
+    } else if bottom.bottom == mask {
+        return Some(Op::FlipVert);
+    } else if bottom.bottom == mask_flip {
+        return Some(Op::Rot180);
+    } else if bottom.right == mask {
+        return Some(Op::Rot270);
+    } else if bottom.right == mask_flip {
+        return Some(Op::FlipDiagBLTR);
+    }
+
+    None
+}
+
+fn print_grid(grid: &HashMap<(i32, i32), Tile>, n: i32) {
+    for y in 0..n {
+        debug!("{}", (0..n).map(
+            |x| grid.get(&(x, y)).map_or(String::from(""), |t| t.id.to_string())
+        ).join(", "));
+    }
+}
+
+pub fn chop_and_assemble(grid: &HashMap<(i32, i32), Tile>, n: i32) -> Grid<bool> {
+    let s = 10i32;
+    let mut g: HashSet::<(i32, i32)> = HashSet::new();
+    for x in 0..n {
+        for y in 0..n {
+            let t = grid.get(&(x, y)).unwrap();
+            let px = &t.px;
+            // Chop off the sides
+            for i in 1..s-1 {
+                for j in 1..s-1 {
+                    if px[(i as usize, j as usize)] {
+                        g.insert(((s-2)*x + (i - 1), (s-2)*y + (j - 1)));
+                    }
+                }
+            }
+        }
+    }
+
+    let size = ((s - 2) * n) as usize;
+    Grid::from_fn(size, size, |x, y| g.contains(&(x as i32, y as i32)))
+}
+
+fn fill_grid(tiles: &[Tile], top_left: &Tile, right: &Tile, below: &Tile, neighbors: HashMap<u64, Vec<&Tile>>) -> HashMap<(i32, i32), Tile> {
+    let n = (tiles.len() as f64).sqrt() as i32;
+    let mut used = set!{top_left.id, right.id, below.id};
+    let id_to_tile = tiles.iter().map(|t| (t.id, t)).collect::<HashMap<u64, &Tile>>();
+
+    // These three should be enough to orient the grid
+    let mut grid = map! {
+        (0, 0) => top_left.clone(),
+        (1, 0) => right.clone(),
+        (0, 1) => below.clone()
+    };
+
+    let neighbs = |id, u: &HashSet<u64>| {
+        neighbors[&id].iter().filter(|&n| !u.contains(&n.id)).copied().collect_vec()
+    };
+
+    // n = 3
+    // diag = 4
+    for diag in 2..(2*n - 1) {
+        if diag < n {
+            let bottom = grid.get(&(0, diag - 1)).unwrap();
+            let bottoms = neighbs(bottom.id, &used).iter()
+                .filter_map(
+                    |t| add_to_bottom(bottom, t)
+                        .map(|op| transform_tile(t, op))
+                ).collect_vec();
+            assert_eq!(1, bottoms.len());
+            let b = &bottoms[0];
+            grid.insert((0, diag), b.clone());
+            used.insert(b.id);
+
+            let right = grid.get(&(diag - 1, 0)).unwrap();
+            let rights = neighbs(right.id, &used).iter()
+                .filter_map(
+                    |t| add_to_right(right, t)
+                        .map(|op| transform_tile(t, op))
+                ).collect_vec();
+            assert_eq!(1, rights.len());
+            let r = &rights[0];
+            grid.insert((diag, 0), r.clone());
+            used.insert(r.id);
+        }
+
+        for x in 0..n {
+            let y = diag - x;
+            if (0..n).contains(&y) && !grid.contains_key(&(x, y)) {
+                // Find the (unique) tile that can go at (x, y)
+                let left = grid.get(&(x - 1, y)).unwrap();
+                let above = grid.get(&(x, y - 1)).unwrap();
+                let left_ids = neighbs(left.id, &used).iter().map(|t| t.id).collect::<HashSet<_>>();
+                let above_ids = neighbs(above.id, &used).iter().map(|t| t.id).collect::<HashSet<_>>();
+                let ids = left_ids.intersection(&above_ids).collect_vec();
+                if ids.len() != 1 {
+                    panic!("Impossible situation: ({}, {}) => {} candidates", x, y, ids.len());
+                }
+                let t = id_to_tile[ids[0]];
+                if let Some(op) = add_to_right(left, t) {
+                    grid.insert((x, y), transform_tile(t, op));
+                    used.insert(t.id);
+                }
+            }
+        }
+    }
+
+    grid
+}
+
+pub fn grid_to_str(px: &Grid<bool>) -> String {
+    px.render(|c| if *c { '#' } else { '.' })
+}
+
+pub fn parse_tiles(text: &str) -> Result<Vec<Tile>, ParseError> {
+    util::parse_paragraphs(text, parse_tile)
+}
+
+pub fn read_tiles(path: &str) -> Result<Vec<Tile>, ParseError> {
+    Source::read(path)?.parse(parse_tiles)
+
+    // Sample: 9 tiles, 27 distinct edges
+    // Input: 144 tiles, 446 distinct edges
+    // Tiles are 10x10
+}
+
+/// Map from each tile's ID to the tiles which could possibly be next to it.
+fn find_neighbors(tiles: &[Tile]) -> HashMap<u64, Vec<&Tile>> {
+    // let edges = tiles.iter().flat_map(|tile| vec![tile.left, tile.right, tile.top, tile.bottom]).collect::<HashSet<_>>();
+
+    info!("# tiles: {}", tiles.len());
+    // println!("# distinct edges: {}", edges.len());
+
+    let mask_to_tiles = index_tiles(tiles);
+    let id_to_tile = tiles.iter().map(|t| (t.id, t)).collect::<HashMap<u64, &Tile>>();
+
+    let mut neighbor_map = HashMap::new();
+    for (i, tile) in tiles.iter().enumerate() {
+        let neighbors = possible_neighbors(tile, &mask_to_tiles).iter().map(|t| t.id).collect_vec();
+        debug!("{} {} -> {:?}", i, tile.id, neighbors);
+        neighbor_map.insert(tile.id, neighbors.iter().map(|id| id_to_tile[id]).collect_vec());
+    }
+    neighbor_map
+}
+
+/// Tiles with only two possible neighbors must be the corners.
+fn find_corners<'a>(tiles: &'a [Tile], neighbor_map: &HashMap<u64, Vec<&Tile>>) -> Vec<&'a Tile> {
+    let corners = tiles.iter().filter(|t| neighbor_map[&t.id].len() == 2).collect_vec();
+    info!("Corners: {:?}", corners.iter().map(|t| t.id).collect_vec());
+    corners
+}
+
+pub fn corner_product(tiles: &[Tile]) -> u64 {
+    let neighbor_map = find_neighbors(tiles);
+    let corners = find_corners(tiles, &neighbor_map);
+    corners.iter().map(|t| t.id).product::<u64>()
+}
+
+/// Assemble the image and return the number of non-dragon cells.
+pub fn count_non_dragons(tiles: &[Tile]) -> Option<usize> {
+    let neighbor_map = find_neighbors(tiles);
+    let corners = find_corners(tiles, &neighbor_map);
+    let mask_to_tiles = index_tiles(tiles);
+
+    assert_eq!(corners.len(), 4);
+    let top_left = corners[0];
+    let tln = possible_neighbors(top_left, &mask_to_tiles);
+    assert_eq!(2, tln.len());
+    // TODO: why can't I move out of this vector?
+    let mut tln0 = tln[0].clone();
+    let mut tln1 = tln[1].clone();
+    let tl_tile = OPS.iter().find_map(|&op| {
+        let t = transform_tile(top_left, op);
+        let op0 = add_to_right(&t, &tln0);
+        let op1 = add_to_bottom(&t, &tln1);
+        if let (Some(op0), Some(op1)) = (op0, op1) {
+            debug!("top left: {}", t.id);
+            debug!("      op: {:?}", op);
+            debug!("   right: {} {:?}", tln0.id, op0);
+            debug!("  bottom: {} {:?}", tln1.id, op1);
+            tln0 = transform_tile(&tln0, op0);
+            tln1 = transform_tile(&tln1, op1);
+            return Some(t);
+        }
+        None
+    }).unwrap();
+
+    let n = (tiles.len() as f64).sqrt() as i32;
+    let grid = fill_grid(tiles, &tl_tile, &tln0, &tln1, neighbor_map);
+    print_grid(&grid, n);
+
+    let pat = transform_px(&chop_and_assemble(&grid, n), Op::FlipDiagTLBR);
+    debug!("Grid:\n{}", grid_to_str(&pat));
+
+    // Only one orientation should have any dragons, so try them all at once.
+    pool::find_first(OPS.len(), |i| {
+        let non_dragons = find_dragons(&transform_px(&pat, OPS[i]));
+        debug!("Non-Dragons ({:?}): {:?}", OPS[i], non_dragons);
+        non_dragons
+    })
+}
+
+/// A random edge of a 10x10 tile between the corner pixels `a` and `b`. Neither it
+/// nor its flip may be in `used`, so that every edge matches up in only one place,
+/// and it can't be a palindrome either, so that matching it fixes the orientation.
+fn random_edge(rng: &mut Rng, a: bool, b: bool, used: &mut HashSet<u32>) -> u32 {
+    loop {
+        let mask = (a as u32) << 9 | (rng.below(256) as u32) << 1 | b as u32;
+        let flipped = flip_bits(mask, 10);
+        if mask != flipped && !used.contains(&mask) && !used.contains(&flipped) {
+            used.insert(mask);
+            used.insert(flipped);
+            return mask;
+        }
+    }
+}
+
+/// How many dragons there are in the image, as drawn.
+fn count_dragons(px: &Grid<bool>) -> usize {
+    let n = px.width() as i32;
+    (0..n)
+        .flat_map(|y| (0..n).map(move |x| (x, y)))
+        .filter(|(x, y)| {
+            DRAGON.iter().all(|(dx, dy)| {
+                px.get((x + dx) as usize, (y + dy) as usize).copied().unwrap_or(false)
+            })
+        })
+        .count()
+}
+
+/// A random image cut into about `size` 10x10 tiles, which are shuffled and
+/// randomly flipped and rotated, with both answers planted.
+///
+/// The image has sparse noise plus some dragons that don't overlap, and is
+/// rerolled on the off chance the noise makes another dragon in any orientation.
+/// Every tile edge is unique, even flipped, which is what the solver relies on.
+pub fn generate_tiles(rng: &mut Rng, size: usize) -> Generated {
+    let n = ((size as f64).sqrt() as usize).clamp(3, 12);
+    let w = 8 * n;
+    let (image, dragons) = loop {
+        let mut taken = Grid::new(w, w, false);
+        let mut px = Grid::from_fn(w, w, |_, _| rng.below(4) == 0);
+        let mut dragons = 0;
+        for _ in 0..n * n {
+            let (x, y) = (rng.below(w as u64 - 19) as usize, rng.below(w as u64 - 2) as usize);
+            if (x..x + 20).any(|i| (y..y + 3).any(|j| taken[(i, j)])) {
+                continue;
+            }
+            for i in x..x + 20 {
+                for j in y..y + 3 {
+                    taken[(i, j)] = true;
+                }
+            }
+            for (dx, dy) in DRAGON.iter() {
+                px[(x + *dx as usize, y + *dy as usize)] = true;
+            }
+            dragons += 1;
+        }
+        let found = OPS.iter().map(|&op| count_dragons(&transform_px(&px, op))).collect_vec();
+        if found[0] == dragons && found.iter().sum::<usize>() == dragons {
+            break (px, dragons);
+        }
+    };
+
+    // Corner pixels are shared by up to four tiles, and edges by two.
+    let corner = Grid::from_fn(n + 1, n + 1, |_, _| rng.bool());
+    let mut used = HashSet::new();
+    let horiz = Grid::from_fn(n, n + 1, |x, y| random_edge(rng, corner[(x, y)], corner[(x + 1, y)], &mut used));
+    let vert = Grid::from_fn(n + 1, n, |x, y| random_edge(rng, corner[(x, y)], corner[(x, y + 1)], &mut used));
+    let bit = |mask: u32, i: usize| mask >> (9 - i) & 1 == 1;
+
+    let mut ids: Vec<u64> = (1000..10000).collect();
+    rng.shuffle(&mut ids);
+    ids.truncate(n * n);
+    let mut tiles = vec![];
+    for y in 0..n {
+        for x in 0..n {
+            let px = Grid::from_fn(10, 10, |i, j| match (i, j) {
+                (_, 0) => bit(horiz[(x, y)], i),
+                (_, 9) => bit(horiz[(x, y + 1)], i),
+                (0, _) => bit(vert[(x, y)], j),
+                (9, _) => bit(vert[(x + 1, y)], j),
+                _ => image[(8 * x + i - 1, 8 * y + j - 1)],
+            });
+            let px = transform_px(&px, *rng.choose(&OPS));
+            tiles.push(format!("Tile {}:\n{}", ids[y * n + x], grid_to_str(&px)));
+        }
+    }
+    rng.shuffle(&mut tiles);
+
+    let corners = ids[0] * ids[n - 1] * ids[n * (n - 1)] * ids[n * n - 1];
+    let rough = image.iter().filter(|(_, &v)| v).count() - DRAGON.len() * dragons;
+    Generated {
+        input: tiles.join("\n\n"),
+        part1: Some(corners.to_string()),
+        part2: Some(rough.to_string()),
+        ..Generated::default()
+    }
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    type Input = Vec<Tile>;
+    const DAY: u32 = 20;
+
+    fn parse(source: &Source, _args: &[String]) -> Result<Vec<Tile>, ParseError> {
+        source.parse(parse_tiles)
+    }
+
+    fn part1(tiles: &Vec<Tile>) -> Option<String> {
+        Some(corner_product(tiles).to_string())
+    }
+
+    fn part2(tiles: &Vec<Tile>) -> Option<String> {
+        count_non_dragons(tiles).map(|n| n.to_string())
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated> {
+        Some(generate_tiles(rng, size))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+    use crate::rng::for_all;
+
+    #[test]
+    fn test_parse_tile() {
+        let tile = parse_tile(r#"Tile 2311:
+        ..##.#
+        ##..#.
+        #...##
+        ####.#
+        ##.##.
+        ##...#"#).unwrap();
+        assert_eq!(tile, Tile {
+            id: 2311,
+            px: Grid::from_rows(vec![
+                vec![false, false, true, true, false, true],
+                vec![true, true, false, false, true, false],
+                vec![true, false, false, false, true, true],
+                vec![true, true, true, true, false, true],
+                vec![true, true, false, true, true, false],
+                vec![true, true, false, false, false, true],
+            ]),
+            top: 1 + 4 + 8,
+            bottom: 1 + 16 + 32,
+            left: 1 + 2 + 4 + 8 + 16,
+            right: 1 + 4 + 8 + 32,
+        });
+    }
+
+    #[test]
+    fn test_flip_bits() {
+        // assert_eq!(flip_bits(0b1, 1), vec![0b1]);
+        // assert_eq!(flip_bits(0b10, 2), vec![0, 0b01]);
+        // assert_eq!(flip_bits(0b100, 3), vec![0, 0, 1]);
+        // assert_eq!(flip_bits(0b110, 3), 0b011);
+        assert_eq!(flip_bits(0b1000, 4), 0b0001);
+        assert_eq!(flip_bits(0b1010, 4), 0b0101);
+    }
+
+    #[test]
+    fn test_parse_pixels() {
+        assert_eq!(
+            parse_pixels(r"..##.#
+            ##..#.
+            #...##
+            ####.#
+            ##.##.
+            ##...#").unwrap(),
+            Grid::from_rows(vec![
+                vec![false, false, true, true, false, true],
+                vec![true, true, false, false, true, false],
+                vec![true, false, false, false, true, true],
+                vec![true, true, true, true, false, true],
+                vec![true, true, false, true, true, false],
+                vec![true, true, false, false, false, true],
+            ])
+        );
+
+        let err = parse_pixels("#.\n#.#").unwrap_err();
+        assert_eq!((2, "Expected 2 cells, got 3"), (err.line, err.message.as_str()));
+        let err = parse_pixels("#.#\n#.#").unwrap_err();
+        assert_eq!("Expected 2 pixels, got 3", err.message);
+    }
+
+    #[test]
+    fn test_rot() {
+        let px = parse_pixels(
+            r"#.#
+               #..
+               ##.").unwrap();
+        assert_eq!(grid_to_str(&px),
+            r"#.#
+              #..
+              ##.".replace(" ", "")
+        );
+        assert_eq!(grid_to_str(&px.rot90()),
+            r"###
+              #..
+              ..#".replace(" ", "")
+        );
+    }
+
+    #[test]
+    fn test_transform_tile() {
+        let tile = parse_tile(
+            r"Tile 123:
+               #.#
+               #..
+               ##.").unwrap();
+        assert_eq!(tile.top, 5);
+        assert_eq!(tile.left, 7);
+
+        assert_eq!(transform_tile(&tile, Op::Identity), tile);
+        assert_eq!(transform_tile(&tile, Op::FlipHoriz), parse_tile(
+            r"Tile 123:
+               #.#
+               ..#
+               .##"
+        ).unwrap());
+        assert_eq!(transform_tile(&tile, Op::FlipVert), parse_tile(
+            r"Tile 123:
+               ##.
+               #..
+               #.#"
+        ).unwrap());
+        assert_eq!(transform_tile(&tile, Op::Rot90), parse_tile(
+            r"Tile 123:
+               ###
+               #..
+               ..#"
+        ).unwrap());
+        assert_eq!(transform_tile(&tile, Op::FlipDiagTLBR), parse_tile(
+            r"Tile 123:
+               ###
+               ..#
+               #.."
+        ).unwrap());
+        assert_eq!(transform_tile(&tile, Op::FlipDiagBLTR), parse_tile(
+            r"Tile 123:
+               ..#
+               #..
+               ###"
+        ).unwrap());
+    }
+
+    fn random_tile(rng: &mut Rng) -> Tile {
+        let n = rng.range(2..11) as usize;
+        let px = Grid::from_fn(n, n, |_, _| rng.bool());
+        parse_tile(&format!("Tile {}:\n{}", rng.range(1..10000), grid_to_str(&px))).unwrap()
+    }
+
+    #[test]
+    fn test_transform_props() {
+        // The edge masks of a transformed tile match its transformed pixels.
+        for_all(200, random_tile, |tile| {
+            OPS.iter().all(|&op| {
+                let px = transform_px(&tile.px, op);
+                let expected = parse_tile(&format!("Tile {}:\n{}", tile.id, grid_to_str(&px))).unwrap();
+                transform_tile(tile, op) == expected
+            })
+        });
+        for_all(200, random_tile, |tile| {
+            let rot = |t: &Tile| transform_tile(t, Op::Rot90);
+            rot(&rot(&rot(&rot(tile)))) == *tile
+        });
+        for_all(200, random_tile, |tile| {
+            OPS.iter().all(|&op| possible_masks(&transform_tile(tile, op)) == possible_masks(tile))
+        });
+        for_all(
+            200,
+            |rng| {
+                let n = rng.range(1..11) as u32;
+                (rng.below(1 << n) as u32, n)
+            },
+            |&(bits, n)| flip_bits(flip_bits(bits, n), n) == bits && flip_bits(bits, n) < 1 << n,
+        );
+    }
+
+    #[test]
+    fn test_add_to_right() {
+        let left = parse_tile(
+            r"Tile 123:
+               #.#
+               #..
+               ##.").unwrap();
+
+        assert_eq!(add_to_right(
+            &left,
+            &parse_tile(
+                r"Tile 2:
+                   ###
+                   .#.
+                   .##").unwrap()), Some(Op::Identity));
+
+        assert_eq!(add_to_right(
+        &left,
+        &parse_tile(
+            r"Tile 2:
+                ...
+                #..
+                #..").unwrap()), Some(Op::Rot90));
+
+        assert_eq!(add_to_right(
+            &left,
+            &parse_tile(
+                r"Tile 2:
+                    #..
+                    ###
+                    #.#").unwrap()), Some(Op::FlipDiagTLBR));
+    }
+
+    #[test]
+    fn test_add_to_bottom() {
+        let top = parse_tile(
+            r"Tile 123:
+               #.#
+               #..
+               ##.").unwrap();
+
+        assert_eq!(add_to_bottom(
+            &top,
+            &parse_tile(
+                r"Tile 2:
+                   ##.
+                   #..
+                   #.#").unwrap()), Some(Op::Identity));
+
+        assert_eq!(add_to_bottom(
+            &top,
+            &parse_tile(
+                r"Tile 123:
+                   #.#
+                   #..
+                   ##.").unwrap()), Some(Op::FlipVert));
+    }
+}
@@ -0,0 +1,271 @@
+use crate::error::ParseError;
+use crate::generate::{self, Generated};
+use crate::rng::Rng;
+use crate::solution::Solution;
+use crate::util::{self, Source};
+use crate::{debug, info};
+use regex::Regex;
+use std::collections::HashSet;
+
+
+lazy_static! {
+  // mxmxvkd kfcds sqjhc nhms (contains dairy, fish)
+  static ref RECIPE_RE: Regex = Regex::new(r#"^ *([a-z ]+) \(contains (.*)\)$"#).unwrap();
+}
+
+/// A recipe's ingredients and (some of) its allergens.
+pub type Recipe = (HashSet<String>, HashSet<String>);
+
+pub fn parse_recipe(recipe: &str) -> Result<Recipe, ParseError> {
+  let caps = RECIPE_RE
+    .captures(recipe)
+    .ok_or_else(|| ParseError::whole(recipe, "Expected a recipe like 'abc def (contains dairy)'"))?;
+  let allergens = caps.get(2).unwrap().as_str();
+  for allergen in allergens.split(", ") {
+    if allergen.is_empty() || !allergen.chars().all(|c| c.is_ascii_lowercase()) {
+      return Err(ParseError::at(recipe, allergen, "Invalid allergen"));
+    }
+  }
+  Ok((
+    caps[1].split(' ').filter(|i| !i.is_empty()).map(String::from).collect(),
+    allergens.split(", ").map(String::from).collect()
+  ))
+}
+
+/// Returns the number of appearances of safe ingredients (the answer to part 1) and the
+/// ingredients which might contain an allergen.
+fn find_unsafe_ingredients<'a>(
+  recipes: &[Recipe],
+  ingredients: &[&'a String],
+  allergens: &[&String]
+) -> (usize, Vec<&'a String>) {
+  let mut answer = 0;
+  let mut unsafe_ingredients = vec![];
+  for &ing in ingredients.iter() {
+    let mut excluded = HashSet::new();
+    let mut num_app = 0;
+    for (ings, alls) in recipes.iter() {
+      if !ings.contains(ing) {
+        excluded.extend(alls.iter());
+      } else {
+        num_app += 1;
+      }
+    }
+    if excluded.len() == allergens.len() {
+      answer += num_app;
+    } else {
+      unsafe_ingredients.push(ing);
+    }
+  }
+
+  info!("Answer (part 1): {}", answer);
+  (answer, unsafe_ingredients)
+}
+
+fn find_one_allergen(
+  recipes: &[Recipe],
+  ingredients: &[&String],
+  allergens: &[&String]
+) -> Option<(String, String)> {
+  for &ing in ingredients.iter() {
+    let mut excluded = HashSet::new();
+    for (ings, alls) in recipes.iter() {
+      if !ings.contains(ing) {
+        excluded.extend(alls.iter());
+      }
+      // if alls.len() == 1 {
+      //   // A recipe like X (contains Y) means that X contains Y.
+      //   return Some((String::from(ing), String::from(alls.iter().next().unwrap())));
+      // }
+    }
+    if excluded.len() == allergens.len() - 1 {
+      let the_one = *allergens.iter().find(|a| !excluded.contains(*a)).unwrap();
+      return Some((String::from(ing), String::from(the_one)));
+    }
+  }
+
+  None
+}
+
+fn filter_recipes(
+  recipes: &[Recipe],
+  ingredients: &[&String],
+  allergens: &[&String]
+) -> Vec<Recipe> {
+  let ing_set = ingredients.iter().map(|i| String::from(*i)).collect::<HashSet<_>>();
+  let all_set = allergens.iter().map(|i| String::from(*i)).collect::<HashSet<_>>();
+
+  // TODO: lots of copying here that feels unnecessary
+  recipes.iter().map(
+    |(ings, alls)| (
+      ings.intersection(&ing_set).map(String::from).collect::<HashSet<_>>(),
+      alls.intersection(&all_set).map(String::from).collect::<HashSet<_>>(),
+    )
+  ).collect::<Vec<_>>()
+}
+
+pub fn parse_recipes(text: &str) -> Result<Vec<Recipe>, ParseError> {
+    util::parse_text_lines(text, parse_recipe)
+}
+
+pub fn read_recipes(path: &str) -> Result<Vec<Recipe>, ParseError> {
+    Source::read(path)?.parse(parse_recipes)
+}
+
+fn all_ingredients_and_allergens(recipes: &[Recipe]) -> (Vec<&String>, Vec<&String>) {
+    let mut all_ingredients = HashSet::new();
+    let mut all_allergens = HashSet::new();
+    for (ings, alls) in recipes.iter() {
+      all_ingredients.extend(ings.iter());
+      all_allergens.extend(alls.iter());
+    }
+    let ingredients = all_ingredients.iter().copied().collect::<Vec<_>>();
+    let allergens = all_allergens.iter().copied().collect::<Vec<_>>();
+
+    info!("{} Ingredients, {} allergens", ingredients.len(), allergens.len());
+    (ingredients, allergens)
+}
+
+pub fn count_safe_appearances(recipes: &[Recipe]) -> usize {
+    let (ingredients, allergens) = all_ingredients_and_allergens(recipes);
+    find_unsafe_ingredients(recipes, &ingredients, &allergens).0
+}
+
+/// None if the recipes don't pin down which ingredient holds each allergen.
+pub fn canonical_dangerous_list(recipes: &[Recipe]) -> Option<String> {
+    let (ingredients, mut allergens) = all_ingredients_and_allergens(recipes);
+    let (_, mut ingredients) = find_unsafe_ingredients(recipes, &ingredients, &allergens);
+    let mut recipes = filter_recipes(recipes, &ingredients, &allergens);
+    debug!("Remaining recipes:");
+    for (ings, alls) in &recipes {
+      debug!(" {:?} -> {:?}", ings, alls);
+    }
+
+    info!("Unsafe ingredients: {}", ingredients.len());
+    info!("Allergens: {}", allergens.len());
+
+    let mut mapping = vec![];
+    while !allergens.is_empty() {
+      let x = find_one_allergen(&recipes, &ingredients, &allergens);
+      if let Some((ing, all)) = x {
+        debug!("{} = {}", all, ing);
+        mapping.push((String::from(&all), String::from(&ing)));
+        // TODO: this seems like a lot of fuss to remove an element from a vector
+        ingredients.remove(ingredients.iter().position(|&x| *x == ing).unwrap());
+        allergens.remove(allergens.iter().position(|&x| *x == all).unwrap());
+        recipes = filter_recipes(&recipes, &ingredients, &allergens);
+      } else {
+        info!("Unable to find an allergen among {} left", allergens.len());
+        return None;
+      }
+    }
+
+    mapping.sort();
+    debug!("mapping: {:?}", mapping);
+    let ings = mapping.iter().map(|(_a, b)| String::from(b)).collect::<Vec<_>>();
+    info!("answer: {}", ings.join(","));
+    Some(ings.join(","))
+}
+
+/// About `size` recipes for three to eight allergens, with both answers planted.
+///
+/// Each allergen is in one ingredient, which is in every recipe that lists it. Two
+/// more recipes per allergen list only that allergen, with its ingredient and
+/// disjoint handfuls of safe ones, so that every other ingredient is missing from
+/// at least one of them. That rules out everything but the right ingredient for each
+/// allergen, which is what both parts need.
+pub fn generate_recipes(rng: &mut Rng, size: usize) -> Generated {
+  let mut allergens = vec!["dairy", "eggs", "fish", "nuts", "peanuts", "sesame", "shellfish", "soy", "wheat"];
+  rng.shuffle(&mut allergens);
+  allergens.truncate(rng.range(3..9) as usize);
+  let num_safe = (10 + size / 2).min(300);
+  let names = generate::words(rng, num_safe + allergens.len());
+  let (safe, dangerous) = names.split_at(num_safe);
+
+  let mut recipes: Vec<(Vec<&String>, Vec<&str>)> = vec![];
+  for (a, &allergen) in allergens.iter().enumerate() {
+    let mut others: Vec<&String> = safe.iter().collect();
+    rng.shuffle(&mut others);
+    for half in others[..8].chunks(4) {
+      recipes.push(([&[&dangerous[a]], half].concat(), vec![allergen]));
+    }
+  }
+  while recipes.len() < size {
+    let mut listed: Vec<usize> = (0..allergens.len()).filter(|_| rng.below(4) == 0).collect();
+    if listed.is_empty() {
+      listed.push(rng.below(allergens.len() as u64) as usize);
+    }
+    let mut ings: Vec<&String> = safe.iter().filter(|_| rng.below(4) == 0).collect();
+    ings.extend(dangerous.iter().enumerate().filter(|(a, _)| listed.contains(a) || rng.bool()).map(|(_, d)| d));
+    recipes.push((ings, listed.iter().map(|&a| allergens[a]).collect()));
+  }
+
+  let safe_appearances: usize = recipes.iter().map(|(ings, _)| ings.iter().filter(|i| safe.contains(i)).count()).sum();
+  let mut dangerous_list: Vec<(&str, &String)> = allergens.iter().copied().zip(dangerous).collect();
+  dangerous_list.sort();
+  rng.shuffle(&mut recipes);
+  let lines: Vec<String> = recipes
+    .into_iter()
+    .map(|(mut ings, alls)| {
+      rng.shuffle(&mut ings);
+      let ings: Vec<&str> = ings.iter().map(|i| i.as_str()).collect();
+      format!("{} (contains {})", ings.join(" "), alls.join(", "))
+    })
+    .collect();
+  Generated {
+    input: lines.join("\n"),
+    part1: Some(safe_appearances.to_string()),
+    part2: Some(dangerous_list.iter().map(|(_, d)| d.as_str()).collect::<Vec<_>>().join(",")),
+    ..Generated::default()
+  }
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    type Input = Vec<Recipe>;
+    const DAY: u32 = 21;
+
+    fn parse(source: &Source, _args: &[String]) -> Result<Vec<Recipe>, ParseError> {
+        source.parse(parse_recipes)
+    }
+
+    fn part1(recipes: &Vec<Recipe>) -> Option<String> {
+        Some(count_safe_appearances(recipes).to_string())
+    }
+
+    fn part2(recipes: &Vec<Recipe>) -> Option<String> {
+        canonical_dangerous_list(recipes)
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated> {
+        Some(generate_recipes(rng, size))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+
+    #[test]
+    fn test_parse_recipe() {
+        let (ings, alls) = parse_recipe("mxmxvkd kfcds sqjhc nhms (contains dairy, fish)").unwrap();
+        assert_eq!(ings.len(), 4);
+        assert!(ings.contains("sqjhc"));
+        assert_eq!(alls.len(), 2);
+        assert!(alls.contains("dairy"));
+        assert!(alls.contains("fish"));
+
+        let err = parse_recipe("mxmxvkd kfcds (contains dairy, Fish)").unwrap_err();
+        assert_eq!((32, 4), (err.column, err.len));
+    }
+
+    #[test]
+    fn test_ambiguous() {
+        // Either ingredient could hold either allergen.
+        let recipes = parse_recipes("aa bb (contains x, y)\naa bb cc (contains x, y)").unwrap();
+        assert_eq!(1, count_safe_appearances(&recipes));
+        assert_eq!(None, canonical_dangerous_list(&recipes));
+    }
+}
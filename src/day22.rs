@@ -0,0 +1,200 @@
+use crate::error::{parse_at, ParseError};
+use crate::generate::Generated;
+use crate::rng::Rng;
+use crate::solution::Solution;
+use crate::util::{self, Source};
+use crate::{debug, info};
+use std::collections::HashSet;
+use itertools::Itertools;
+
+#[derive(Clone)]
+pub struct GameState {
+    pub p1: Vec<i32>,
+    pub p2: Vec<i32>,
+}
+
+impl GameState {
+    fn is_done(&self) -> bool {
+        self.p1.is_empty() || self.p2.is_empty()
+    }
+
+    fn state_str(&self) -> String {
+        format!("{};{}",
+            self.p1.iter().map(|x| x.to_string()).join(","),
+            self.p2.iter().map(|x| x.to_string()).join(","),
+        )
+    }
+
+    fn print(&self) {
+        debug!("Player 1: {}\nPlayer 2: {}\n",
+            self.p1.iter().map(|x| x.to_string()).join(", "),
+            self.p2.iter().map(|x| x.to_string()).join(", "),
+        )
+    }
+}
+
+pub fn play_one_round(mut state: GameState) -> GameState {
+    if state.is_done() {
+        return state;
+    }
+
+    let p1 = &mut state.p1;
+    let p2 = &mut state.p2;
+
+    let c1 = p1[0];
+    let c2 = p2[0];
+
+    // If both players have at least as many cards remaining in their deck as the value
+    // of the card they just drew, the winner of the round is determined by playing a
+    // new game of Recursive Combat (see below).
+    let round_winner;
+    if p1.len() as i32 > c1 && p2.len() as i32 > c2 {
+        // recursive combat
+        let recur_state = GameState {
+            p1: p1[1usize..=c1 as usize].to_vec(),
+            p2: p2[1usize..=c2 as usize].to_vec(),
+        };
+        debug!("recursing");
+        recur_state.print();
+        let (who, _cards) = play_game(recur_state);
+        round_winner = who;
+    } else if c1 > c2 {
+        round_winner = 1;
+    } else {
+        round_winner = 2;
+    }
+
+    if round_winner == 1 {
+        debug!("Player 1 wins this round");
+        p1.remove(0);
+        p2.remove(0);
+        p1.push(c1);
+        p1.push(c2);
+    } else {
+        debug!("Player 2 wins this round");
+        p1.remove(0);
+        p2.remove(0);
+        p2.push(c2);
+        p2.push(c1);
+    }
+
+    state
+}
+
+// L-TWC-2642611
+
+
+
+pub fn play_game(mut state: GameState) -> (i32, Vec<i32>) {
+    let mut prev_states = HashSet::new();
+    while !state.is_done() {
+        state.print();
+        let state_str = state.state_str();
+        if prev_states.contains(&state_str) {
+            debug!("Same state as before! P1 wins!");
+            return (1, state.p1);
+        }
+        state = play_one_round(state);
+        prev_states.insert(state_str);
+    }
+
+    if state.p1.is_empty() {
+        (2, state.p2)
+    } else {
+        (1, state.p1)
+    }
+}
+
+fn parse_deck(chunk: &str, player: usize) -> Result<Vec<i32>, ParseError> {
+    let mut lines = chunk.lines();
+    let header = lines.next().unwrap_or("");
+    let expected = format!("Player {}:", player);
+    if header != expected {
+        return Err(ParseError::whole(header, &format!("Expected '{}'", expected)));
+    }
+    lines
+        .enumerate()
+        .map(|(i, line)| parse_at::<i32>(line, line).map_err(|e| e.shift(i + 1)))
+        .collect()
+}
+
+pub fn parse_decks(text: &str) -> Result<GameState, ParseError> {
+    let chunks = util::paragraphs(text);
+    if chunks.len() != 2 {
+        return Err(ParseError::general(&format!("Expected two decks, got {}", chunks.len())));
+    }
+
+    let decks = chunks
+        .iter()
+        .enumerate()
+        .map(|(i, &(start, chunk))| parse_deck(chunk, i + 1).map_err(|e| e.shift(start)))
+        .collect::<Result<Vec<_>, _>>()?;
+    let (p1cards, p2cards) = decks.into_iter().collect_tuple().unwrap();
+
+    Ok(GameState { p1: p1cards, p2: p2cards })
+}
+
+pub fn read_decks(path: &str) -> Result<GameState, ParseError> {
+    Source::read(path)?.parse(parse_decks)
+}
+
+/// A shuffled deck of about `size` cards, up to 50 like the real input, dealt into
+/// two even hands. Recursive Combat gets slow fast as the decks grow: some decks of
+/// 60 cards take half a minute. It has no shortcut, so the answer is left to the solver.
+pub fn generate_decks(rng: &mut Rng, size: usize) -> Generated {
+    let n = (size / 2).clamp(2, 25);
+    let mut cards: Vec<i32> = (1..=2 * n as i32).collect();
+    rng.shuffle(&mut cards);
+    let (p1, p2) = cards.split_at(n);
+    Generated::new(format!("Player 1:\n{}\n\nPlayer 2:\n{}", p1.iter().join("\n"), p2.iter().join("\n")))
+}
+
+pub fn score(hand: &[i32]) -> i32 {
+    let n = hand.len() as i32;
+    hand.iter().enumerate().map(|(i, card)| (n - i as i32) * card).sum::<i32>()
+}
+
+pub fn play_recursive_combat(state: &GameState) -> i32 {
+    let (who, winning_hand) = play_game(state.clone());
+    info!("Winner: player {}", who);
+    info!("Winning hand: {:?}", winning_hand);
+    score(&winning_hand)
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    type Input = GameState;
+    const DAY: u32 = 22;
+
+    fn parse(source: &Source, _args: &[String]) -> Result<GameState, ParseError> {
+        source.parse(parse_decks)
+    }
+
+    fn part2(state: &GameState) -> Option<String> {
+        Some(play_recursive_combat(state).to_string())
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated> {
+        Some(generate_decks(rng, size))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+
+    #[test]
+    fn test_play_game() {
+        let state = GameState {
+            p1: vec![9, 2, 6, 3, 1],
+            p2: vec![5, 8, 4, 7, 10],
+        };
+        let (who, hand) = play_game(state);
+        assert_eq!(who, 2);
+        assert_eq!(hand, vec![7, 5, 6, 2, 4, 1, 10, 8, 9, 3]);
+        assert_eq!(score(&hand), 291);
+    }
+}
@@ -0,0 +1,205 @@
+use std::fmt;
+
+use crate::error::ParseError;
+use crate::generate::Generated;
+use crate::rng::Rng;
+use crate::solution::{parse_arg, Solution};
+use crate::util::Source;
+use crate::{debug, info};
+
+pub struct Cups {
+    // 0 is empty
+    nexts: Vec<usize>,
+    current: usize,
+}
+
+impl Cups {
+    /// Start with the given cups, followed by the rest of 1..=num_cups in order.
+    pub fn new(nums: &[usize], num_cups: usize) -> Cups {
+        let mut nexts = vec![0; num_cups + 1];
+        for (&v0, &v1) in nums.iter().zip(nums.iter().skip(1)) {
+            nexts[v0] = v1;
+        }
+        nexts[*nums.last().unwrap()] = nums.len() + 1;
+
+        for (n, next) in nexts.iter_mut().enumerate().skip(nums.len() + 1) {
+            *next = n + 1;
+        }
+        if num_cups > nums.len() {
+            nexts[num_cups] = nums[0];
+        } else {
+            nexts[*nums.last().unwrap()] = nums[0];
+        }
+
+        Cups { current: nums[0], nexts }
+    }
+
+    fn remove_three_after(&mut self, c0: usize) -> (usize, usize, usize) {
+        let c1 = self.nexts[c0];
+        let c2 = self.nexts[c1];
+        let c3 = self.nexts[c2];
+        let c4 = self.nexts[c3];
+        self.nexts[c0] = c4;
+
+        (c1, c2, c3)
+    }
+
+    fn insert_after(&mut self, c0: usize, val: usize) {
+        let old_next = self.nexts[c0];
+        self.nexts[c0] = val;
+        self.nexts[val] = old_next;
+    }
+
+    fn find_dest(&self, cur: usize, (c1, c2, c3): (usize, usize, usize)) -> usize {
+        debug!("find_dest({}, {:?})", cur, (c1, c2, c3));
+        let mut dest = cur - 1;
+        if dest == 0 {
+            dest += self.nexts.len() - 1;
+        }
+
+        if dest != c1 && dest != c2 && dest != c3 {
+            return dest;
+        }
+
+        self.find_dest(dest, (c1, c2, c3))
+    }
+
+    /// The cup clockwise of `cup`.
+    pub fn next(&self, cup: usize) -> usize {
+        self.nexts[cup]
+    }
+
+    pub fn play_one_round(&mut self) {
+        debug!("cups: {}", self);
+        let (c1, c2, c3) = self.remove_three_after(self.current);
+        debug!("pick up: {}, {}, {}", c1, c2, c3);
+        let dest = self.find_dest(self.current, (c1, c2, c3));
+        debug!("destination: {}", dest);
+
+        self.insert_after(dest, c3);
+        self.insert_after(dest, c2);
+        self.insert_after(dest, c1);
+
+        self.current = self.nexts[self.current];
+    }
+}
+
+
+impl fmt::Display for Cups {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut s = String::with_capacity(self.nexts.len() * 2 + 2);
+        s.push_str(&format!("({})", self.current));
+        let mut next = self.nexts[self.current];
+        let mut x = 0;
+        while next != self.current && x < 25 {
+            s.push(' ');
+            s.push_str(&format!("{}", next));
+            next = self.nexts[next];
+            x += 1;
+        }
+        write!(f, "{}", s)
+    }
+}
+
+pub fn play_game(nums: &[usize], num_rounds: usize) -> usize {
+    let mut cups = Cups::new(nums, 1_000_000);
+    debug!("Cups: {}", cups);
+
+    for i in 1..=num_rounds {
+        debug!("-- move {} --", i);
+        cups.play_one_round();
+    }
+
+    debug!("-- final --");
+    debug!("cups: {}", cups);
+    let c1 = cups.nexts[1];
+    let c2 = cups.nexts[c1];
+    info!("two right of 1: {} * {} = {}",
+        c1, c2, c1 * c2,
+    );
+
+    c1 * c2
+}
+
+/// Parse a line of cup labels, which must be some ordering of 1..=n for n >= 5.
+pub fn parse_cups(line: &str) -> Result<Vec<usize>, ParseError> {
+    let mut cups = vec![];
+    for (i, c) in line.chars().enumerate() {
+        match c.to_digit(10) {
+            Some(d) if d > 0 && !cups.contains(&(d as usize)) => cups.push(d as usize),
+            _ => return Err(ParseError::new(line, i + 1, 1, &format!("Invalid cup: {}", c))),
+        }
+    }
+    if cups.len() < 5 || cups.iter().any(|&c| c > cups.len()) {
+        return Err(ParseError::whole(line, "Expected the cups to be labeled 1 through n (n >= 5)"));
+    }
+    Ok(cups)
+}
+
+pub fn read_cups(path: &str) -> Result<Vec<usize>, ParseError> {
+    Source::read(path)?.parse(parse_cups)
+}
+
+/// A random order for the nine labeled cups. There are always a million cups, so
+/// the size is the number of rounds to play, which is passed as an arg.
+pub fn generate_cups(rng: &mut Rng, size: usize) -> Generated {
+    let mut cups: Vec<usize> = (1..=9).collect();
+    rng.shuffle(&mut cups);
+    Generated {
+        args: vec![size.to_string()],
+        ..Generated::new(cups.iter().map(|c| c.to_string()).collect())
+    }
+}
+
+pub struct Game {
+    pub cups: Vec<usize>,
+    pub num_rounds: usize,
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    type Input = Game;
+    const DAY: u32 = 23;
+
+    /// The optional arg is the number of rounds, which defaults to ten million.
+    fn parse(source: &Source, args: &[String]) -> Result<Game, ParseError> {
+        let num_rounds = parse_arg(args, 0, 10_000_000)?;
+        Ok(Game {
+            cups: source.parse(parse_cups)?,
+            num_rounds,
+        })
+    }
+
+    fn part2(game: &Game) -> Option<String> {
+        Some(play_game(&game.cups, game.num_rounds).to_string())
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated> {
+        Some(generate_cups(rng, size))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+
+    #[test]
+    fn test_play_one_round() {
+        let mut cups = Cups::new(&[3, 8, 9, 1, 2, 5, 4, 6, 7], 9);
+        assert_eq!(cups.to_string(), "(3) 8 9 1 2 5 4 6 7");
+        cups.play_one_round();
+        assert_eq!(cups.to_string(), "(2) 8 9 1 5 4 6 7 3");
+        for _ in 1..10 {
+            cups.play_one_round();
+        }
+        assert_eq!(cups.nexts[1], 9);
+        assert_eq!(cups.nexts[9], 2);
+    }
+}
+
+// ideas:
+// - use a better structure (vecdeque? linked list?)
+// - figure out a math-y way to do it
@@ -0,0 +1,323 @@
+use std::collections::HashSet;
+use itertools::Itertools;
+
+use crate::error::ParseError;
+use crate::generate::Generated;
+use crate::life::{Hex, Life, Rules, Topology};
+use crate::rng::Rng;
+use crate::solution::{parse_arg, Solution};
+use crate::util::Source;
+use crate::{debug, info};
+
+// 0   0 1 2 3 4
+// 1  0 1 2 3 4
+// 2   0 1 2 3 4
+// 3  0 1 2 3 4
+
+// east, southeast, southwest, west, northwest, and northeast.
+// e, se, sw, w, nw, and ne
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum HexDir {
+    E,
+    SE,
+    SW,
+    W,
+    NW,
+    NE,
+}
+
+pub const DIRS: [HexDir; 6] = [HexDir::E, HexDir::SE, HexDir::SW, HexDir::W, HexDir::NW, HexDir::NE];
+
+pub fn hexmove(pos: &(i32, i32), dir: &HexDir) -> (i32, i32) {
+    use HexDir::*;
+    let &(x, y) = pos;
+    match dir {
+        E => (x + 1, y),
+        W => (x - 1, y),
+        // TODO: is this clearer with guards on the matches?
+        NE => (if y.abs() % 2 == 1 { x } else { x + 1 }, y - 1 ),
+        NW => (if y.abs() % 2 == 1 { x - 1 } else { x }, y - 1 ),
+        SE => (if y.abs() % 2 == 1 { x } else { x + 1 }, y + 1 ),
+        SW => (if y.abs() % 2 == 1 { x - 1 } else { x }, y + 1 ),
+    }
+}
+
+pub fn hexmoves(moves: &[HexDir]) -> (i32, i32) {
+    // let mut pos = (0, 0);
+    // for dir in moves {
+    //     pos = hexmove(pos, dir);
+    //     println!("{:?} -> {:?}", dir, pos);
+    // }
+    moves.iter().fold((0, 0), |pos, dir| hexmove(&pos, dir))
+    // pos
+}
+
+pub fn parse_line(line: &str) -> Result<Vec<HexDir>, ParseError> {
+    let cs = line.chars().collect_vec();
+    let mut r = Vec::with_capacity(cs.len());
+    let mut i = 0usize;
+    while i < cs.len() {
+        let c = cs[i];
+        i += 1;
+        if c == 'e' {
+            r.push(HexDir::E);
+        } else if c == 'w' {
+            r.push(HexDir::W);
+        } else if c == 'n' || c == 's' {
+            let c1 = cs.get(i).copied().unwrap_or(' ');
+            r.push(match (c, c1) {
+                ('n', 'e') => HexDir::NE,
+                ('n', 'w') => HexDir::NW,
+                ('s', 'e') => HexDir::SE,
+                ('s', 'w') => HexDir::SW,
+                _ => {
+                    let msg = format!("Expected {}e or {}w", c, c);
+                    return Err(ParseError::new(line, i, 2, &msg));
+                }
+            });
+            i += 1;
+        } else {
+            return Err(ParseError::new(line, i, 1, &format!("Invalid direction: {}", c)));
+        }
+    }
+    Ok(r)
+}
+
+/// A white tile with exactly 2 black neighbors flips to black, and a black tile
+/// with zero or more than 2 black neighbors flips to white.
+pub const RULES: &str = "B2/S12";
+
+pub fn neighbors(pos: &(i32, i32)) -> Vec<(i32, i32)> {
+    Hex.neighbors(pos)
+}
+
+/// The black tiles after following each path and flipping the tile at its end.
+pub fn flip_tiles(paths: &[Vec<HexDir>]) -> HashSet<(i32, i32)> {
+    let mut black = HashSet::new();
+    for m in paths {
+        let pos = hexmoves(m);
+        if !black.remove(&pos) {
+            black.insert(pos);
+        }
+    }
+    black
+}
+
+pub fn run_days(paths: &[Vec<HexDir>], rules: &Rules, num_days: usize) -> usize {
+    let mut floor = Life::new(Hex, rules.clone(), flip_tiles(paths));
+
+    debug!("Grid: {:?}", floor.active());
+    info!("num black: {}", floor.num_active());
+
+    for day in 1..=num_days {
+        floor.step();
+        debug!("Day {}: {}", day, floor.num_active());
+    }
+    floor.num_active()
+}
+
+/// The paths to the tiles to flip, and how to run the exhibit.
+pub struct Lobby {
+    pub paths: Vec<Vec<HexDir>>,
+    pub num_days: usize,
+    pub rules: Rules,
+}
+
+/// `size` random paths of up to twenty steps, with the answer to part 1 planted.
+/// The ends are tracked in axial coordinates rather than with `hexmove`, so that
+/// the two can be checked against each other. The paths stay close to the
+/// reference tile so that plenty of them end on the same tiles.
+pub fn generate_paths(rng: &mut Rng, size: usize) -> Generated {
+    let steps = [("e", (1, 0)), ("w", (-1, 0)), ("ne", (1, -1)), ("nw", (0, -1)), ("se", (0, 1)), ("sw", (-1, 1))];
+    let mut black = HashSet::new();
+    let mut lines = vec![];
+    for _ in 0..size {
+        let (mut q, mut r) = (0, 0);
+        let mut line = String::new();
+        for _ in 0..rng.range(1..21) {
+            let (dir, (dq, dr)) = *rng.choose(&steps);
+            line += dir;
+            q += dq;
+            r += dr;
+        }
+        if !black.remove(&(q, r)) {
+            black.insert((q, r));
+        }
+        lines.push(line);
+    }
+    Generated {
+        input: lines.join("\n"),
+        part1: Some(black.len().to_string()),
+        ..Generated::default()
+    }
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    type Input = Lobby;
+    const DAY: u32 = 24;
+
+    /// Optional args are the number of days, which defaults to 100, and the rules for
+    /// flipping tiles, which default to `B2/S12`.
+    fn parse(source: &Source, args: &[String]) -> Result<Lobby, ParseError> {
+        let paths = source.parse_lines(parse_line)?;
+        let num_days = parse_arg(args, 0, 100)?;
+        let rules = match args.get(1) {
+            Some(r) => Rules::parse(r).map_err(|e| ParseError::arg(r, &e.message))?,
+            None => Rules::parse(RULES).unwrap(),
+        };
+        Ok(Lobby { paths, num_days, rules })
+    }
+
+    fn part1(lobby: &Lobby) -> Option<String> {
+        Some(flip_tiles(&lobby.paths).len().to_string())
+    }
+
+    fn part2(lobby: &Lobby) -> Option<String> {
+        Some(run_days(&lobby.paths, &lobby.rules, lobby.num_days).to_string())
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated> {
+        Some(generate_paths(rng, size))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+    use crate::rng::{for_all, Rng};
+
+    #[test]
+    fn test_parse_line() {
+        use HexDir::*;
+        assert_eq!(parse_line("seswneswswsenwwnwse"), Ok(vec![SE, SW, NE, SW, SW, SE, NW, W, NW, SE]));
+        assert_eq!(parse_line("nwwswee"), Ok(vec![NW, W, SW, E, E]));
+
+        let err = parse_line("eenxw").unwrap_err();
+        assert_eq!((3, 2), (err.column, err.len));
+        assert!(parse_line("ees").is_err());
+    }
+
+    #[test]
+    fn test_move() {
+        assert_eq!((1, 1), hexmoves(&parse_line("esew").unwrap()));
+        assert_eq!((0, 0), hexmoves(&parse_line("nwwswee").unwrap()));
+    }
+
+    // [HexDir::E, HexDir::SE, HexDir::SW, HexDir::W, HexDir::NW, HexDir::NE];
+    #[test]
+    fn test_neighbors() {
+        assert_eq!(neighbors(&(0, 0)), vec![
+            (1, 0),
+            (1, 1),
+            (0, 1),
+            (-1, 0),
+            (0, -1),
+            (1, -1),
+        ]);
+    }
+
+    #[test]
+    fn test_hexmove_neighbors() {
+        for &pos in [(0, 0), (3, 1), (-2, -1), (5, -4)].iter() {
+            let moved = DIRS.iter().map(|d| hexmove(&pos, d)).collect_vec();
+            assert_eq!(neighbors(&pos), moved);
+        }
+    }
+
+    #[test]
+    fn test_num_neighbors() {
+        let rules = Rules::parse(RULES).unwrap();
+        let floor = Life::new(Hex, rules.clone(), vec![(1, 0), (1, 1), (0, 1), (-1, 0), (0, -1), (1, -1)]);
+        assert_eq!(floor.num_active_neighbors(&(0, 0)), 6);
+
+        let floor = Life::new(Hex, rules.clone(), vec![(1, 0), (1, 1), (1, -1)]);
+        assert_eq!(floor.num_active_neighbors(&(0, 0)), 3);
+
+        let floor = Life::new(Hex, rules, vec![(1, 0), (1, 1), (0, 1), (1, -1)]);
+        assert_eq!(floor.num_active_neighbors(&(0, 0)), 4);
+    }
+
+    #[test]
+    fn test_next_day() {
+        let rules = Rules::parse(RULES).unwrap();
+        // A lone black tile flips to white, and none of its neighbors flip.
+        let mut floor = Life::new(Hex, rules.clone(), vec![(0, 0)]);
+        floor.step();
+        assert_eq!(0, floor.num_active());
+
+        let mut floor = Life::new(Hex, rules, vec![(0, 0), (1, 0)]);
+        floor.step();
+        assert_eq!(4, floor.num_active());
+    }
+
+    #[test]
+    fn test_flip_tiles() {
+        let paths = vec![parse_line("esew").unwrap(), parse_line("nwwswee").unwrap(), parse_line("").unwrap()];
+        assert_eq!(flip_tiles(&paths), [(1, 1)].iter().copied().collect());
+    }
+
+    fn opposite(dir: &HexDir) -> HexDir {
+        use HexDir::*;
+        match dir {
+            E => W,
+            SE => NW,
+            SW => NE,
+            W => E,
+            NW => SE,
+            NE => SW,
+        }
+    }
+
+    fn random_pos(rng: &mut Rng) -> (i32, i32) {
+        (rng.range(-50..51) as i32, rng.range(-50..51) as i32)
+    }
+
+    #[test]
+    fn test_hexmove_props() {
+        use HexDir::*;
+        for_all(500, random_pos, |pos| hexmove(&hexmove(pos, &E), &W) == *pos);
+        for_all(500, random_pos, |pos| DIRS.iter().all(|d| hexmove(&hexmove(pos, d), &opposite(d)) == *pos));
+        // Walking once around a neighbor comes back to the start.
+        for_all(500, random_pos, |pos| {
+            [E, SE, SW, W, NW, NE].iter().fold(*pos, |p, d| hexmove(&p, d)) == *pos
+        });
+        // Any path followed by the reverse of its opposite comes back to the start.
+        let gen = |rng: &mut Rng| {
+            let path = (0..rng.range(0..20)).map(|_| rng.below(6) as usize).collect_vec();
+            (random_pos(rng), path)
+        };
+        for_all(500, gen, |(pos, path)| {
+            let there = path.iter().fold(*pos, |p, &d| hexmove(&p, &DIRS[d]));
+            let back = path.iter().rev().fold(there, |p, &d| hexmove(&p, &opposite(&DIRS[d])));
+            back == *pos
+        });
+    }
+
+    #[test]
+    fn test_parse_line_props() {
+        // In the same order as DIRS.
+        let names = ["e", "se", "sw", "w", "nw", "ne"];
+        let gen = |rng: &mut Rng| (0..rng.range(0..30)).map(|_| rng.below(6) as usize).collect_vec();
+        for_all(500, gen, |path| {
+            let line = path.iter().map(|&d| names[d]).join("");
+            parse_line(&line) == Ok(path.iter().map(|&d| DIRS[d].clone()).collect())
+        });
+    }
+
+//  y NW, W, SW, E, E
+// -1 -1 0 1 2 3 4
+//  0  -1 0 1 2 3 4
+//  1 -1 0 1 2 3 4
+//  2  -1 0 1 2 3 4
+//  3 -1 0 1 2 3 4
+
+}
+
+// ideas:
+// - use a better structure (vecdeque? linked list?)
+// - figure out a math-y way to do it
@@ -0,0 +1,115 @@
+use itertools::Itertools;
+
+use crate::error::{parse_at, ParseError};
+use crate::generate::Generated;
+use crate::rng::Rng;
+use crate::solution::Solution;
+use crate::util::{self, Source};
+
+pub fn transform(sub: u64, v: u64) -> u64 {
+  (v * sub) % 20201227
+}
+
+pub fn find_loop_size(target: u64) -> usize {
+  let sub = 7u64;
+  let mut v = 1u64;
+  let mut i = 0usize;
+  while v != target {
+    v = transform(sub, v);
+    i += 1;
+  }
+  i
+}
+
+pub fn forward(v: u64, sub: u64, loop_size: usize) -> u64 {
+  let mut v = v;
+  for _ in 0..loop_size {
+    v = transform(sub, v);
+    // println!("{}: {}", i, v);
+  }
+  v
+}
+
+pub fn find_encryption_key(v0: u64, v1: u64) -> u64 {
+  let loop_size = find_loop_size(v0);
+  forward(1, v1, loop_size)
+
+  // for num_str in &args[1..] {
+  //   let num = num_str.parse::<u64>().unwrap();
+  //   // let loop_size = find_loop_size(num);
+  //   println!("{}: {}", num, find_loop_size(num));
+  // }
+}
+
+/// The card's and the door's public keys.
+pub struct PublicKeys(pub u64, pub u64);
+
+pub fn parse_keys(text: &str) -> Result<PublicKeys, ParseError> {
+  let keys = util::parse_text_lines(text, |line| match parse_at::<u64>(line, line)? {
+    k if k == 0 || k >= 20201227 => Err(ParseError::whole(line, "Keys must be between 1 and 20201226")),
+    k => Ok(k),
+  })?;
+  match keys.into_iter().collect_tuple() {
+    Some((card, door)) => Ok(PublicKeys(card, door)),
+    None => Err(ParseError::general("Expected exactly two public keys")),
+  }
+}
+
+/// `base` to the power `exp`, mod 20201227, by repeated squaring.
+fn pow_mod(base: u64, exp: u64) -> u64 {
+  let (mut result, mut base, mut exp) = (1, base % 20201227, exp);
+  while exp > 0 {
+    if exp & 1 == 1 {
+      result = transform(base, result);
+    }
+    base = transform(base, base);
+    exp >>= 1;
+  }
+  result
+}
+
+/// Public keys from secret loop sizes below `size` thousand, with the encryption
+/// key planted. The solver searches for the loop size, so the size sets how long
+/// that takes.
+pub fn generate_keys(rng: &mut Rng, size: usize) -> Generated {
+  let max = (size as i64 * 1000).clamp(2, 20201226);
+  let card_loop = rng.range(1..max) as u64;
+  let door_loop = rng.range(1..max) as u64;
+  let (card, door) = (pow_mod(7, card_loop), pow_mod(7, door_loop));
+  Generated {
+    input: format!("{}\n{}", card, door),
+    part1: Some(pow_mod(door, card_loop).to_string()),
+    ..Generated::default()
+  }
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+  type Input = PublicKeys;
+  const DAY: u32 = 25;
+
+  fn parse(source: &Source, _args: &[String]) -> Result<PublicKeys, ParseError> {
+    source.parse(parse_keys)
+  }
+
+  fn part1(keys: &PublicKeys) -> Option<String> {
+    Some(find_encryption_key(keys.0, keys.1).to_string())
+  }
+
+  fn generate(rng: &mut Rng, size: usize) -> Option<Generated> {
+    Some(generate_keys(rng, size))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_find_loop_size() {
+    assert_eq!(find_loop_size(5764801), 8);
+    assert_eq!(find_loop_size(17807724), 11);
+    assert_eq!(find_encryption_key(5764801, 17807724), 14897079);
+  }
+}
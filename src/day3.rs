@@ -0,0 +1,229 @@
+use std::fmt;
+
+use crate::error::ParseError;
+use crate::generate::Generated;
+use crate::grid::{Edges, Grid};
+use crate::rng::Rng;
+use crate::solution::{check_flags, flag_values, Solution};
+use crate::util::Source;
+use crate::{debug, info};
+
+#[derive(PartialEq, Clone, Copy)]
+pub enum Cell {
+    Open,
+    Tree,
+}
+
+impl fmt::Display for Cell {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", if *self == Cell::Open { '.' } else { '#' })
+    }
+}
+
+pub type Forest = Grid<Cell>;
+
+fn parse_cell(c: char) -> Option<Cell> {
+    match c {
+        '.' => Some(Cell::Open),
+        '#' => Some(Cell::Tree),
+        _ => None,
+    }
+}
+
+pub fn parse_forest(text: &str) -> Result<Forest, ParseError> {
+    Grid::parse(text, parse_cell)
+}
+
+pub fn read_forest(path: &str) -> Result<Forest, ParseError> {
+    Source::read(path)?.grid(parse_cell)
+}
+
+/// How far right and down the toboggan goes at each step.
+pub type Slope = (usize, usize);
+
+/// The slopes part 2 checks by default.
+pub const SLOPES: [Slope; 5] = [(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)];
+
+/// Whether the path for `slope` crosses `(x, y)`, with the forest repeating to the
+/// right. The toboggan starts at the top left, so that square doesn't count.
+fn on_path(f: &Forest, (dx, dy): Slope, x: usize, y: usize) -> bool {
+    y > 0 && y.is_multiple_of(dy) && (y / dy * dx) % f.width() == x
+}
+
+pub fn count_trees(f: &Forest, (dx, dy): Slope) -> usize {
+    let mut x = dx;
+    let mut y = dy;
+    let mut trees = 0;
+    let mut open = 0;
+
+    while y < f.height() {
+        // The forest repeats to the right.
+        if f.at(x as i64, y as i64, Edges::Wrap) == Some(&Cell::Tree) {
+            trees += 1;
+        } else {
+            open += 1;
+        }
+        x += dx;
+        y += dy;
+    }
+
+    info!("Right {}, down {}: hit {} trees, {} open squares", dx, dy, trees, open);
+    trees
+}
+
+/// The product of the trees hit on each slope.
+pub fn tree_product(f: &Forest, slopes: &[Slope]) -> usize {
+    slopes.iter().map(|&slope| count_trees(f, slope)).product()
+}
+
+/// The forest with the path for `slope` drawn over it: `O` where the toboggan crosses an
+/// open square and `X` where it hits a tree. The path wraps around at the right edge,
+/// rather than the forest being repeated.
+pub fn render_path(f: &Forest, slope: Slope) -> Grid<char> {
+    Grid::from_fn(f.width(), f.height(), |x, y| {
+        let cell = *f.get(x, y).unwrap();
+        match (on_path(f, slope, x, y), cell) {
+            (true, Cell::Open) => 'O',
+            (true, Cell::Tree) => 'X',
+            (false, Cell::Open) => '.',
+            (false, Cell::Tree) => '#',
+        }
+    })
+}
+
+/// Parse a slope like `3,1`.
+pub fn parse_slope(text: &str) -> Result<Slope, ParseError> {
+    let slope = text.split_once(',').and_then(|(dx, dy)| Some((dx.parse().ok()?, dy.parse().ok()?)));
+    match slope {
+        Some((_, 0)) => Err(ParseError::arg(text, "The toboggan must go down")),
+        Some(slope) => Ok(slope),
+        None => Err(ParseError::arg(text, &format!("Expected a slope like '3,1', not '{}'", text))),
+    }
+}
+
+pub struct Toboggan {
+    pub forest: Forest,
+    /// The slopes for part 2. Part 1 uses the first one.
+    pub slopes: Vec<Slope>,
+    /// A slope whose path is drawn with the answers.
+    pub render: Option<Slope>,
+}
+
+/// A forest 31 squares wide and `size` high, with about one tree in five. The
+/// answers are tallied as the rows are made, from the default slopes.
+pub fn generate_forest(rng: &mut Rng, size: usize) -> Generated {
+    let width = 31;
+    let mut trees = [0; SLOPES.len()];
+    let rows: Vec<String> = (0..size.max(1))
+        .map(|y| {
+            let row: Vec<Cell> = (0..width)
+                .map(|_| if rng.below(5) == 0 { Cell::Tree } else { Cell::Open })
+                .collect();
+            for (&(dx, dy), trees) in SLOPES.iter().zip(trees.iter_mut()) {
+                if y > 0 && y.is_multiple_of(dy) && row[y / dy * dx % width] == Cell::Tree {
+                    *trees += 1;
+                }
+            }
+            row.iter().map(|c| c.to_string()).collect()
+        })
+        .collect();
+    Generated {
+        input: rows.join("\n"),
+        part1: Some(trees[1].to_string()),
+        part2: Some(trees.iter().product::<usize>().to_string()),
+        ..Generated::default()
+    }
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    type Input = Toboggan;
+    const DAY: u32 = 3;
+
+    /// Optional args are `--slope DX,DY` flags, which replace the usual slopes, and
+    /// `--render DX,DY` to draw a path over the forest. Part 1 counts the trees on the
+    /// first slope, which is right 3, down 1 by default, and the count for each slope is
+    /// shown with the answers.
+    fn parse(source: &Source, args: &[String]) -> Result<Toboggan, ParseError> {
+        check_flags(args, &["--slope", "--render"])?;
+        let mut slopes = flag_values(args, "--slope")?.into_iter().map(parse_slope).collect::<Result<Vec<_>, _>>()?;
+        if slopes.is_empty() {
+            slopes = vec![(3, 1)];
+            slopes.extend(SLOPES.iter().filter(|&&slope| slope != (3, 1)));
+        }
+        let render = flag_values(args, "--render")?.last().map(|s| parse_slope(s)).transpose()?;
+        let forest = source.grid(parse_cell)?;
+        debug!("Forest: {}x{}", forest.width(), forest.height());
+        Ok(Toboggan { forest, slopes, render })
+    }
+
+    fn extras(t: &Toboggan) -> Result<Vec<String>, String> {
+        let mut lines: Vec<String> = t
+            .slopes
+            .iter()
+            .map(|&(dx, dy)| format!("Right {}, down {}: {} trees", dx, dy, count_trees(&t.forest, (dx, dy))))
+            .collect();
+        if let Some(slope) = t.render {
+            lines.extend(render_path(&t.forest, slope).to_string().lines().map(String::from));
+        }
+        Ok(lines)
+    }
+
+    fn part1(t: &Toboggan) -> Option<String> {
+        Some(count_trees(&t.forest, t.slopes[0]).to_string())
+    }
+
+    fn part2(t: &Toboggan) -> Option<String> {
+        Some(tree_product(&t.forest, &t.slopes).to_string())
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated> {
+        Some(generate_forest(rng, size))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+
+    const SAMPLE: &str = "..##.......
+#...#...#..
+.#....#..#.
+..#.#...#.#
+.#...##..#.
+..#.##.....
+.#.#.#....#
+.#........#
+#.##...#...
+#...##....#
+.#..#...#.#";
+
+    #[test]
+    fn test_slopes() {
+        let forest = parse_forest(SAMPLE).unwrap();
+        let counts: Vec<usize> = SLOPES.iter().map(|&slope| count_trees(&forest, slope)).collect();
+        assert_eq!(vec![2, 7, 3, 4, 2], counts);
+        assert_eq!(336, tree_product(&forest, &SLOPES));
+
+        assert_eq!(Ok((3, 1)), parse_slope("3,1"));
+        assert!(parse_slope("3,0").is_err());
+        assert!(parse_slope("3").is_err());
+        assert!(parse_slope("-1,1").is_err());
+    }
+
+    #[test]
+    fn test_render_path() {
+        let forest = parse_forest(SAMPLE).unwrap();
+        let path = render_path(&forest, (3, 1)).to_string();
+        let lines: Vec<&str> = path.lines().collect();
+        assert_eq!("..##.......", lines[0]);
+        assert_eq!("#..O#...#..", lines[1]);
+        assert_eq!(".#....X..#.", lines[2]);
+        // The path wraps around to the left edge.
+        assert_eq!(".#..#...X.#", lines[10]);
+        assert_eq!(7, path.matches('X').count());
+        assert_eq!(3, path.matches('O').count());
+    }
+}
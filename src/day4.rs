@@ -0,0 +1,265 @@
+use crate::error::ParseError;
+use crate::generate::Generated;
+use crate::rng::Rng;
+use crate::schema::{FieldError, Rule, Schema};
+use crate::solution::{check_flags, flag_values, Solution};
+use crate::util::{self, OwnedRecord, RecordFormat, Source};
+use crate::debug;
+use itertools::Itertools;
+
+const ECLS: [&str; 7] = ["amb", "blu", "brn", "gry", "grn", "hzl", "oth"];
+
+lazy_static! {
+    /// The rules for passports. The country ID is optional, and can be anything.
+    pub static ref PASSPORT: Schema = Schema::new()
+        .required("byr", Rule::year(1920, 2002))
+        .required("iyr", Rule::year(2010, 2020))
+        .required("eyr", Rule::year(2020, 2030))
+        .required("hgt", Rule::measure(&[("cm", 150, 193), ("in", 59, 76)]))
+        .required("hcl", Rule::pattern(r"^#[0-9a-f]{6}$"))
+        .required("ecl", Rule::one_of(&ECLS))
+        .required("pid", Rule::pattern(r"^[0-9]{9}$"))
+        .optional("cid", Rule::Any);
+}
+
+/// Every problem with a passport, according to `PASSPORT`.
+pub fn check_passport(passport: &Passport) -> Vec<FieldError<'_>> {
+    let errors = PASSPORT.check(&passport.map());
+    debug!(
+        "Passport at line {}: {}",
+        passport.line,
+        if errors.is_empty() { String::from("valid") } else { errors.iter().join(", ") }
+    );
+    errors
+}
+
+/// A passport, as it was written in the input.
+pub type Passport = OwnedRecord;
+
+pub fn parse_passports(text: &str) -> Result<Vec<Passport>, ParseError> {
+    util::parse_records(text, RecordFormat::default(), |record| Ok(Passport::from(record)))
+}
+
+pub fn read_passports(path: &str) -> Result<Vec<Passport>, ParseError> {
+    Source::read(path)?.parse_records(RecordFormat::default(), |record| Ok(Passport::from(record)))
+}
+
+/// Each passport's lines and whether it's valid, followed by its missing fields and
+/// the invalid ones with the rules they break.
+pub fn report(passports: &[Passport]) -> String {
+    let mut out = String::new();
+    let mut num_valid = 0;
+    for passport in passports {
+        let errors = check_passport(passport);
+        let status = if errors.is_empty() { "valid" } else { "invalid" };
+        out += &format!("Lines {}-{}: {}\n", passport.line, passport.last_line(), status);
+        for error in errors.iter() {
+            out += &format!("  {}\n", error);
+        }
+        num_valid += errors.is_empty() as usize;
+    }
+    out += &format!("{} valid, {} invalid\n", num_valid, passports.len() - num_valid);
+    out
+}
+
+/// The passports which are valid (or not), written out as they were in the input.
+pub fn export(passports: &[Passport], valid: bool) -> String {
+    let texts: Vec<&str> = passports
+        .iter()
+        .filter(|passport| check_passport(passport).is_empty() == valid)
+        .map(|passport| passport.text.as_str())
+        .collect();
+    if texts.is_empty() {
+        String::new()
+    } else {
+        texts.join("\n\n") + "\n"
+    }
+}
+
+/// Each field with a few valid values and a few that break one rule or another.
+fn field_values(rng: &mut Rng, key: &str) -> (String, Vec<String>) {
+    let year = |rng: &mut Rng, lo: i64, hi: i64| rng.range(lo..hi + 1).to_string();
+    let (valid, invalid) = match key {
+        "byr" => (year(rng, 1920, 2002), vec!["1919", "2003", "19200", "abcd"]),
+        "iyr" => (year(rng, 2010, 2020), vec!["2009", "2021", "201", "20x0"]),
+        "eyr" => (year(rng, 2020, 2030), vec!["2019", "2031", "02025", "2O25"]),
+        "hgt" => (
+            if rng.bool() {
+                format!("{}cm", rng.range(150..194))
+            } else {
+                format!("{}in", rng.range(59..77))
+            },
+            vec!["149cm", "194cm", "58in", "77in", "170", "60cm", "180in", "1.8m"],
+        ),
+        "hcl" => (
+            format!("#{:06x}", rng.below(1 << 24)),
+            vec!["#12345", "#1234567", "123abc", "#abcdeg", "#ABCDEF"],
+        ),
+        "ecl" => (
+            rng.choose(&ECLS).to_string(),
+            vec!["wat", "amber", "blue", "#123abc", "gmt"],
+        ),
+        "pid" => (
+            format!("{:09}", rng.below(1_000_000_000)),
+            vec!["01234567", "0123456789", "12345678a", "#123456"],
+        ),
+        _ => (rng.range(1..1000).to_string(), vec![]),
+    };
+    (valid, invalid.into_iter().map(String::from).collect())
+}
+
+/// `size` passports, each of which is valid or has a single problem: a missing
+/// field, or one whose value breaks one of its rules. The country ID comes and goes
+/// regardless, since it doesn't matter.
+pub fn generate_passports(rng: &mut Rng, size: usize) -> Generated {
+    let keys = ["byr", "iyr", "eyr", "hgt", "hcl", "ecl", "pid", "cid"];
+    let mut records = vec![];
+    let mut num_complete = 0;
+    let mut num_valid = 0;
+    for _ in 0..size {
+        let broken = if rng.bool() { Some(rng.below(7) as usize) } else { None };
+        let missing = rng.bool();
+        let mut fields = vec![];
+        for (i, key) in keys.iter().enumerate() {
+            let (valid, invalid) = field_values(rng, key);
+            let value = match broken {
+                Some(b) if b == i && missing => continue,
+                Some(b) if b == i => rng.choose(&invalid).clone(),
+                _ if *key == "cid" && rng.bool() => continue,
+                _ => valid,
+            };
+            fields.push(format!("{}:{}", key, value));
+        }
+        rng.shuffle(&mut fields);
+        num_complete += !(broken.is_some() && missing) as usize;
+        num_valid += broken.is_none() as usize;
+
+        let mut record = String::new();
+        for (i, field) in fields.iter().enumerate() {
+            if i > 0 {
+                record.push(if rng.below(3) == 0 { '\n' } else { ' ' });
+            }
+            record += field;
+        }
+        records.push(record);
+    }
+    Generated {
+        input: records.join("\n\n"),
+        part1: Some(num_complete.to_string()),
+        part2: Some(num_valid.to_string()),
+        ..Generated::default()
+    }
+}
+
+pub struct Solver;
+
+pub struct Passports {
+    pub passports: Vec<Passport>,
+    /// Where to write a report on every passport, with `-` to show it with the answers.
+    pub report: Vec<String>,
+    /// Where to copy just the valid passports.
+    pub write_valid: Vec<String>,
+    /// Where to copy just the invalid passports.
+    pub write_invalid: Vec<String>,
+}
+
+impl Solution for Solver {
+    type Input = Passports;
+    const DAY: u32 = 4;
+
+    /// Optional args are `--report PATH` to write a report on every passport (shown
+    /// with the answers for `-`), and `--write-valid PATH` and `--write-invalid PATH`
+    /// to copy just the valid or invalid passports to a new file.
+    fn parse(source: &Source, args: &[String]) -> Result<Passports, ParseError> {
+        check_flags(args, &["--report", "--write-valid", "--write-invalid"])?;
+        let paths = |flag| -> Result<Vec<String>, ParseError> {
+            Ok(flag_values(args, flag)?.into_iter().map(String::from).collect())
+        };
+        Ok(Passports {
+            passports: source.parse(parse_passports)?,
+            report: paths("--report")?,
+            write_valid: paths("--write-valid")?,
+            write_invalid: paths("--write-invalid")?,
+        })
+    }
+
+    fn extras(input: &Passports) -> Result<Vec<String>, String> {
+        let mut lines = vec![];
+        for path in input.report.iter() {
+            match path.as_str() {
+                "-" => lines.extend(report(&input.passports).lines().map(String::from)),
+                _ => util::write_file(path, &report(&input.passports))?,
+            }
+        }
+        for path in input.write_valid.iter() {
+            util::write_file(path, &export(&input.passports, true))?;
+        }
+        for path in input.write_invalid.iter() {
+            util::write_file(path, &export(&input.passports, false))?;
+        }
+        Ok(lines)
+    }
+
+    /// Passports with all their required fields.
+    fn part1(input: &Passports) -> Option<String> {
+        let num_ok = input
+            .passports
+            .iter()
+            .filter(|passport| !check_passport(passport).iter().any(|e| matches!(e, FieldError::Missing(_))))
+            .count();
+        Some(num_ok.to_string())
+    }
+
+    /// Passports with no problems at all.
+    fn part2(input: &Passports) -> Option<String> {
+        let num_ok = input.passports.iter().filter(|passport| check_passport(passport).is_empty()).count();
+        Some(num_ok.to_string())
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated> {
+        Some(generate_passports(rng, size))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+
+    #[test]
+    fn test_check_passport() {
+        let passport = |text| parse_passports(text).unwrap().remove(0);
+        assert!(check_passport(&passport("pid:087499704 hgt:74in ecl:grn iyr:2012 eyr:2030 byr:1980\nhcl:#623a2f")).is_empty());
+
+        // Every problem is reported, in the schema's order.
+        let errors: Vec<String> = check_passport(&passport("eyr:1972 cid:100\nhcl:#18171d ecl:amb hgt:170 pid:186cm iyr:2018"))
+            .iter()
+            .map(|e| e.to_string())
+            .collect();
+        assert_eq!(
+            vec![
+                "missing byr",
+                "invalid eyr:1972 (expected 4 digits from 2020 to 2030)",
+                "invalid hgt:170 (expected 150 to 193cm or 59 to 76in)",
+                "invalid pid:186cm (expected a match for ^[0-9]{9}$)",
+            ],
+            errors
+        );
+    }
+
+    #[test]
+    fn test_report() {
+        let text = "ecl:gry pid:860033327 eyr:2020 hcl:#fffffd\nbyr:1937 iyr:2017 cid:147 hgt:183cm\n\n\
+                    iyr:2013 ecl:amb cid:350 eyr:2023 pid:028048884\nhcl:#cfa07d byr:1929";
+        let passports = parse_passports(text).unwrap();
+        assert_eq!((4, 5), (passports[1].line, passports[1].last_line()));
+        assert_eq!(
+            "Lines 1-2: valid\nLines 4-5: invalid\n  missing hgt\n1 valid, 1 invalid\n",
+            report(&passports)
+        );
+        assert_eq!(format!("{}\n", passports[0].text), export(&passports, true));
+        assert_eq!(format!("{}\n", passports[1].text), export(&passports, false));
+        assert_eq!(("iyr", "2013"), (passports[1].fields[0].0.as_str(), passports[1].fields[0].1.as_str()));
+        assert_eq!(passports[1].text, parse_passports(&util::normalize(&export(&passports, false))).unwrap()[0].text);
+    }
+}
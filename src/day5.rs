@@ -0,0 +1,130 @@
+use crate::error::ParseError;
+use crate::generate::Generated;
+use crate::rng::Rng;
+use crate::solution::Solution;
+use crate::util::{self, Source};
+
+/// A boarding pass like `FBFBBFFRLR`: seven F/B characters for the row, then three
+/// L/R characters for the column. Each is a binary number, with B and R as 1s.
+pub fn parse_seat(line: &str) -> Result<u32, ParseError> {
+    if line.chars().count() != 10 {
+        return Err(ParseError::whole(line, "Expected 10 characters, like FBFBBFFRLR"));
+    }
+    line.chars().enumerate().try_fold(0, |id, (i, c)| {
+        let bit = match (i < 7, c) {
+            (true, 'F') | (false, 'L') => 0,
+            (true, 'B') | (false, 'R') => 1,
+            _ => {
+                let expected = if i < 7 { "F or B" } else { "L or R" };
+                let msg = format!("Expected {}, got {}", expected, c);
+                return Err(ParseError::new(line, i + 1, 1, &msg));
+            }
+        };
+        Ok(2 * id + bit)
+    })
+}
+
+pub fn row(id: u32) -> u32 {
+    id >> 3
+}
+
+pub fn column(id: u32) -> u32 {
+    id & 7
+}
+
+/// The seat IDs, i.e. row * 8 + column.
+pub fn parse_seats(text: &str) -> Result<Vec<u32>, ParseError> {
+    util::parse_text_lines(text, parse_seat)
+}
+
+pub fn read_seats(path: &str) -> Result<Vec<u32>, ParseError> {
+    Source::read(path)?.parse(parse_seats)
+}
+
+/// Your seat is the only empty one whose neighbors on either side are both taken.
+pub fn find_missing_seat(ids: &[u32]) -> Option<u32> {
+    let mut ids = ids.to_vec();
+    ids.sort_unstable();
+    ids.windows(2).find(|w| w[1] == w[0] + 2).map(|w| w[0] + 1)
+}
+
+/// The boarding pass for a seat ID, the inverse of `parse_seat`.
+pub fn seat_code(id: u32) -> String {
+    (0..10)
+        .map(|i| {
+            let bit = id >> (9 - i) & 1 == 1;
+            match (i < 7, bit) {
+                (true, false) => 'F',
+                (true, true) => 'B',
+                (false, false) => 'L',
+                (false, true) => 'R',
+            }
+        })
+        .collect()
+}
+
+/// The boarding passes for a run of about `size` consecutive seats, shuffled, with
+/// one missing from the middle.
+pub fn generate_seats(rng: &mut Rng, size: usize) -> Generated {
+    let n = size.clamp(3, 1000) as u32;
+    let first = rng.below(1024 - n as u64) as u32;
+    let missing = first + 1 + rng.below(n as u64 - 2) as u32;
+    let mut codes: Vec<String> = (first..first + n).filter(|&id| id != missing).map(seat_code).collect();
+    rng.shuffle(&mut codes);
+    Generated {
+        input: codes.join("\n"),
+        part1: Some((first + n - 1).to_string()),
+        part2: Some(missing.to_string()),
+        ..Generated::default()
+    }
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    type Input = Vec<u32>;
+    const DAY: u32 = 5;
+
+    fn parse(source: &Source, _args: &[String]) -> Result<Vec<u32>, ParseError> {
+        source.parse(parse_seats)
+    }
+
+    fn part1(ids: &Vec<u32>) -> Option<String> {
+        ids.iter().max().map(|id| id.to_string())
+    }
+
+    fn part2(ids: &Vec<u32>) -> Option<String> {
+        find_missing_seat(ids).map(|id| id.to_string())
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated> {
+        Some(generate_seats(rng, size))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+
+    #[test]
+    fn test_parse_seat() {
+        let id = parse_seat("FBFBBFFRLR").unwrap();
+        assert_eq!((44, 5, 357), (row(id), column(id), id));
+        assert_eq!(Ok(567), parse_seat("BFFFBBFRRR"));
+        assert_eq!(Ok(119), parse_seat("FFFBBBFRRR"));
+        assert_eq!(Ok(820), parse_seat("BBFFBBFRLL"));
+
+        let err = parse_seat("FBFBBFFRBR").unwrap_err();
+        assert_eq!((9, "Expected L or R, got B"), (err.column, err.message.as_str()));
+        assert!(parse_seat("FBFBBFFRL").is_err());
+        assert_eq!("FBFBBFFRLR", seat_code(357));
+        assert!((0..1024).all(|id| parse_seat(&seat_code(id)) == Ok(id)));
+    }
+
+    #[test]
+    fn test_find_missing_seat() {
+        assert_eq!(Some(12), find_missing_seat(&[14, 11, 10, 13]));
+        assert_eq!(None, find_missing_seat(&[10, 11, 12]));
+    }
+}
@@ -0,0 +1,163 @@
+use crate::error::ParseError;
+use crate::generate::Generated;
+use crate::rng::Rng;
+use crate::solution::{check_flags, flag_values, parse_flag, Solution};
+use crate::util::{self, Record, RecordFormat, Source};
+
+/// Each person's answers, as a bitset with bit 0 for question `a`.
+#[derive(Debug)]
+pub struct Group {
+    pub people: Vec<u32>,
+}
+
+impl Group {
+    /// The questions anyone answered "yes" to.
+    pub fn anyone(&self) -> u32 {
+        self.people.iter().fold(0, |any, p| any | p)
+    }
+
+    /// The questions everyone answered "yes" to.
+    pub fn everyone(&self) -> u32 {
+        self.people.iter().fold(self.anyone(), |all, p| all & p)
+    }
+
+    /// The questions at least `k` people answered "yes" to.
+    pub fn quorum(&self, k: usize) -> u32 {
+        (0..26)
+            .filter(|q| self.people.iter().filter(|&&p| p >> q & 1 == 1).count() >= k)
+            .fold(0, |qs, q| qs | 1 << q)
+    }
+}
+
+/// Each person's answers are a bare field, usually on a line of their own.
+const GROUP_FORMAT: RecordFormat = RecordFormat { pair: None, delimiter: None };
+
+pub fn parse_group(record: &Record) -> Result<Group, ParseError> {
+    let mut people = vec![];
+    for person in record.fields.iter() {
+        let mut answers = 0;
+        for (i, c) in person.value.char_indices() {
+            if !c.is_ascii_lowercase() {
+                let msg = format!("Invalid question: {}", c);
+                return Err(record.error(person, &person.value[i..i + c.len_utf8()], &msg));
+            }
+            answers |= 1 << (c as u8 - b'a');
+        }
+        people.push(answers);
+    }
+    Ok(Group { people })
+}
+
+pub fn parse_groups(text: &str) -> Result<Vec<Group>, ParseError> {
+    util::parse_records(text, GROUP_FORMAT, parse_group)
+}
+
+pub fn read_groups(path: &str) -> Result<Vec<Group>, ParseError> {
+    Source::read(path)?.parse_records(GROUP_FORMAT, parse_group)
+}
+
+/// `size` groups of one to five people. Each person answers yes to some of the
+/// questions the group has in common, plus about a third of the others.
+pub fn generate_groups(rng: &mut Rng, size: usize) -> Generated {
+    let mut groups = vec![];
+    let mut num_any_yes = 0;
+    let mut num_all_yes = 0;
+    for _ in 0..size {
+        let common: u32 = (0..26).filter(|_| rng.below(4) == 0).map(|q| 1 << q).sum();
+        // Nobody answers nothing, since they'd have a blank line.
+        let people: Vec<u32> = (0..rng.range(1..6))
+            .map(|_| common | (0..26).filter(|_| rng.below(3) == 0).map(|q| 1 << q).sum::<u32>())
+            .filter(|&p| p != 0)
+            .collect();
+        if people.is_empty() {
+            continue;
+        }
+        let group = Group { people };
+        num_any_yes += group.anyone().count_ones();
+        num_all_yes += group.everyone().count_ones();
+        let lines: Vec<String> = group
+            .people
+            .iter()
+            .map(|&p| {
+                let mut qs: Vec<char> = (0..26).filter(|q| p >> q & 1 == 1).map(|q| (b'a' + q) as char).collect();
+                rng.shuffle(&mut qs);
+                qs.into_iter().collect()
+            })
+            .collect();
+        groups.push(lines.join("\n"));
+    }
+    Generated {
+        input: groups.join("\n\n"),
+        part1: Some(num_any_yes.to_string()),
+        part2: Some(num_all_yes.to_string()),
+        ..Generated::default()
+    }
+}
+
+pub struct Solver;
+
+pub struct Groups {
+    pub groups: Vec<Group>,
+    /// How many people in a group must answer a question for part 2 to count it, or
+    /// None for everyone.
+    pub quorum: Option<usize>,
+}
+
+impl Solution for Solver {
+    type Input = Groups;
+    const DAY: u32 = 6;
+
+    /// The optional arg is `--quorum K`, which makes part 2 count the questions at least
+    /// K people in each group answered, rather than everyone.
+    fn parse(source: &Source, args: &[String]) -> Result<Groups, ParseError> {
+        check_flags(args, &["--quorum"])?;
+        let quorum = match flag_values(args, "--quorum")?.last() {
+            Some(value) => match parse_flag(args, "--quorum", 0)? {
+                0 => return Err(ParseError::arg(value, "The quorum must be at least 1")),
+                k => Some(k),
+            },
+            None => None,
+        };
+        Ok(Groups { groups: source.parse(parse_groups)?, quorum })
+    }
+
+    fn part1(input: &Groups) -> Option<String> {
+        let num_any_yes: u32 = input.groups.iter().map(|g| g.anyone().count_ones()).sum();
+        Some(num_any_yes.to_string())
+    }
+
+    fn part2(input: &Groups) -> Option<String> {
+        let num_yes: u32 = input
+            .groups
+            .iter()
+            .map(|g| match input.quorum {
+                Some(k) => g.quorum(k),
+                None => g.everyone(),
+            })
+            .map(u32::count_ones)
+            .sum();
+        Some(num_yes.to_string())
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated> {
+        Some(generate_groups(rng, size))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+
+    #[test]
+    fn test_group() {
+        let groups = parse_groups("abc\n\nab\nac\n\nb\nb\nbz").unwrap();
+        assert_eq!((vec![0b111], vec![0b11, 0b101]), (groups[0].people.clone(), groups[1].people.clone()));
+        assert_eq!((0b111, 0b1), (groups[1].anyone(), groups[1].everyone()));
+        assert_eq!((1 << 25 | 0b10, 0b10), (groups[2].anyone(), groups[2].everyone()));
+        assert_eq!((0b10, 1 << 25 | 0b10, 0), (groups[2].quorum(2), groups[2].quorum(1), groups[2].quorum(4)));
+
+        let err = parse_groups("abc\n\nab\naC").unwrap_err();
+        assert_eq!((4, 2, "Invalid question: C"), (err.line, err.column, err.message.as_str()));
+    }
+}
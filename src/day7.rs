@@ -0,0 +1,380 @@
+use crate::error::{parse_at, ParseError};
+use crate::generate::{self, Generated};
+use crate::rng::Rng;
+use crate::solution::{check_flags, parse_flag, Solution};
+use crate::util::{self, Source};
+use crate::debug;
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+lazy_static! {
+    // muted yellow bags contain 2 shiny gold bags, 9 faded blue bags.
+    static ref LINE_RE: Regex = Regex::new(r"([a-z ]+) bags contain (.*)\.$").unwrap();
+    // 1 dark olive bag
+    // 2 vibrant plum bags
+    static ref BAG_RE: Regex = Regex::new(r"(\d+) ([a-z ]+) bags?").unwrap();
+}
+
+/// A bag color and the colors and counts of bags it must contain.
+pub type Rule = (String, Vec<(String, u32)>);
+
+/// An index into a `BagGraph`'s colors.
+pub type ColorId = usize;
+
+/// A loop of colors, each of which must contain the next, and the last the first.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cycle(pub Vec<String>);
+
+impl fmt::Display for Cycle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} -> {}", self.0.join(" -> "), self.0[0])
+    }
+}
+
+/// The rules as a graph, with each color given an ID in the order it's first seen.
+/// Colors which are only ever contained get a rule with no contents. The rules can't
+/// have cycles, so the number of bags inside each bag is worked out up front.
+#[derive(Debug, Clone)]
+pub struct BagGraph {
+    names: Vec<String>,
+    ids: HashMap<String, ColorId>,
+    contents: Vec<Vec<(ColorId, u32)>>,
+    containers: Vec<Vec<ColorId>>,
+    inside: Vec<u64>,
+}
+
+impl BagGraph {
+    /// Build the graph from a list of rules, with later rules for a color replacing
+    /// earlier ones. The totals saturate rather than overflow.
+    pub fn new(rules: &[Rule]) -> Result<BagGraph, Cycle> {
+        let mut graph = BagGraph {
+            names: vec![],
+            ids: HashMap::new(),
+            contents: vec![],
+            containers: vec![],
+            inside: vec![],
+        };
+        for (color, bags) in rules.iter() {
+            let id = graph.intern(color);
+            graph.contents[id] = bags.iter().map(|(bag, count)| (graph.intern(bag), *count)).collect();
+        }
+        for (id, bags) in graph.contents.iter().enumerate() {
+            for &(bag, _) in bags.iter() {
+                graph.containers[bag].push(id);
+            }
+        }
+        graph.inside = graph.count_inside()?;
+        Ok(graph)
+    }
+
+    fn intern(&mut self, color: &str) -> ColorId {
+        if let Some(&id) = self.ids.get(color) {
+            return id;
+        }
+        let id = self.names.len();
+        self.names.push(String::from(color));
+        self.ids.insert(String::from(color), id);
+        self.contents.push(vec![]);
+        self.containers.push(vec![]);
+        id
+    }
+
+    /// The number of bags inside each bag, from a depth first search which stops at
+    /// the first cycle. The search keeps its own stack, since chains of bags can be long.
+    fn count_inside(&self) -> Result<Vec<u64>, Cycle> {
+        const NEW: u8 = 0;
+        const OPEN: u8 = 1;
+        const DONE: u8 = 2;
+        let mut state = vec![NEW; self.len()];
+        let mut inside = vec![0u64; self.len()];
+        for root in 0..self.len() {
+            if state[root] != NEW {
+                continue;
+            }
+            state[root] = OPEN;
+            let mut stack = vec![(root, 0)];
+            while let Some((id, next)) = stack.last_mut() {
+                let id = *id;
+                if let Some(&(bag, _)) = self.contents[id].get(*next) {
+                    *next += 1;
+                    match state[bag] {
+                        NEW => {
+                            state[bag] = OPEN;
+                            stack.push((bag, 0));
+                        }
+                        OPEN => {
+                            let start = stack.iter().position(|&(i, _)| i == bag).unwrap();
+                            return Err(Cycle(stack[start..].iter().map(|&(i, _)| self.names[i].clone()).collect()));
+                        }
+                        _ => {}
+                    }
+                } else {
+                    inside[id] = self.contents[id]
+                        .iter()
+                        .map(|&(bag, count)| (count as u64).saturating_mul(1 + inside[bag]))
+                        .fold(0, u64::saturating_add);
+                    state[id] = DONE;
+                    stack.pop();
+                }
+            }
+        }
+        Ok(inside)
+    }
+
+    /// The number of colors.
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    pub fn id(&self, color: &str) -> Option<ColorId> {
+        self.ids.get(color).copied()
+    }
+
+    pub fn name(&self, id: ColorId) -> &str {
+        &self.names[id]
+    }
+
+    /// The colors and counts of the bags directly inside an `id` bag.
+    pub fn contents(&self, id: ColorId) -> &[(ColorId, u32)] {
+        &self.contents[id]
+    }
+
+    /// Every color reachable from `id` along `edges`, not including `id`, in ID order.
+    fn reachable<F: Fn(ColorId) -> Vec<ColorId>>(&self, id: ColorId, edges: F) -> Vec<ColorId> {
+        let mut seen = vec![false; self.len()];
+        let mut fringe = vec![id];
+        while let Some(color) = fringe.pop() {
+            for next in edges(color) {
+                if !seen[next] {
+                    seen[next] = true;
+                    fringe.push(next);
+                }
+            }
+        }
+        (0..self.len()).filter(|&i| seen[i] && i != id).collect()
+    }
+
+    /// The colors of bag which could (eventually) contain an `id` bag.
+    pub fn ancestors(&self, id: ColorId) -> Vec<ColorId> {
+        self.reachable(id, |color| self.containers[color].clone())
+    }
+
+    /// The colors of bag which an `id` bag (eventually) contains.
+    pub fn descendants(&self, id: ColorId) -> Vec<ColorId> {
+        self.reachable(id, |color| self.contents[color].iter().map(|&(bag, _)| bag).collect())
+    }
+
+    /// How many bags an `id` bag contains, all the way down.
+    pub fn bags_inside(&self, id: ColorId) -> u64 {
+        self.inside[id]
+    }
+}
+
+/// Parse one rule, e.g. "muted yellow bags contain 2 shiny gold bags, 9 faded blue bags."
+pub fn parse_rule(line: &str) -> Result<(String, Vec<(String, u32)>), ParseError> {
+    let line_groups = LINE_RE
+        .captures(line)
+        .ok_or_else(|| ParseError::whole(line, "Expected a rule like 'x bags contain 1 y bag.'"))?;
+    let subject = &line_groups[1];
+    let contents_str = line_groups.get(2).unwrap().as_str();
+    if contents_str == "no other bags" {
+        return Ok((String::from(subject), Vec::new()));
+    }
+
+    let bag = contents_str
+        .split(", ")
+        .map(|part| {
+            let cap = BAG_RE
+                .captures(part)
+                .ok_or_else(|| ParseError::at(line, part, "Expected a count and color like '2 shiny gold bags'"))?;
+            Ok((String::from(&cap[2]), parse_at::<u32>(line, cap.get(1).unwrap().as_str())?))
+        })
+        .collect::<Result<Vec<_>, ParseError>>()?;
+    Ok((String::from(subject), bag))
+}
+
+/// The span of `color` where a rule's line lists it among the bag's contents.
+fn contained_color<'a>(line: &'a str, color: &str) -> &'a str {
+    let contents = LINE_RE.captures(line).unwrap().get(2).unwrap().as_str();
+    let bag = BAG_RE.captures_iter(contents).find(|cap| &cap[2] == color).unwrap();
+    &contents[bag.get(2).unwrap().range()]
+}
+
+/// Parse the rules into a graph, checking that every color has a rule and that no
+/// bag has to contain itself.
+pub fn parse_rules(text: &str) -> Result<BagGraph, ParseError> {
+    let rules = util::parse_text_lines(text, parse_rule)?;
+    let colors: HashSet<&String> = rules.iter().map(|(color, _)| color).collect();
+    for (i, ((subject, bag), line)) in rules.iter().zip(text.lines()).enumerate() {
+        if let Some((color, _)) = bag.iter().find(|(color, _)| !colors.contains(color)) {
+            let msg = format!("There's no rule for {} bags", color);
+            return Err(ParseError::at(line, contained_color(line, color), &msg).shift(i));
+        }
+        debug!("Bag {} contents: {:?}", subject, bag);
+    }
+    BagGraph::new(&rules).map_err(|cycle| {
+        // Point at the next color in the cycle, in the last rule for the first color.
+        let names = &cycle.0;
+        let i = rules.iter().rposition(|(color, _)| *color == names[0]).unwrap();
+        let line = text.lines().nth(i).unwrap();
+        let next = &names[1 % names.len()];
+        let at = contained_color(line, next);
+        ParseError::at(line, at, &format!("Bags can't contain themselves: {}", cycle)).shift(i)
+    })
+}
+
+pub fn read_rules(path: &str) -> Result<BagGraph, ParseError> {
+    Source::read(path)?.parse(parse_rules)
+}
+
+/// A random set of rules for `size` colors of bag (up to 900), including shiny gold,
+/// with both answers planted. Bags only contain bags later in a hidden order, so the
+/// rules form a DAG, and no bag holds more than a million others.
+pub fn generate_rules(rng: &mut Rng, size: usize) -> Generated {
+    const MAX_INSIDE: u64 = 1_000_000;
+    let n = size.clamp(2, 900);
+    let mut colors = generate::color_names(rng, n);
+    if !colors.iter().any(|c| c == "shiny gold") {
+        colors[0] = String::from("shiny gold");
+    }
+    let gold = rng.range(n as i64 / 4..(3 * n as i64 / 4).max(n as i64 / 4 + 1)) as usize;
+    let i = colors.iter().position(|c| c == "shiny gold").unwrap();
+    colors.swap(i, gold);
+
+    // Fill in the contents from the end, so each bag's total is known when it's used.
+    // Bags mostly hold bags close to them in the order, which makes for deep chains.
+    // Only the totals for shiny gold and the bags it can hold need to stay small.
+    let mut contents: Vec<Vec<(usize, u64)>> = vec![vec![]; n];
+    let mut inside = vec![0u64; n];
+    for i in (0..n - 1).rev() {
+        // Make sure shiny gold holds something, and that something holds it.
+        let num_kinds = if i == gold { rng.range(1..5) } else { rng.below(5) as i64 };
+        if i + 1 == gold {
+            contents[i].push((gold, rng.range(1..6) as u64));
+        }
+        for _ in 0..num_kinds {
+            let j = (i + 1 + rng.below(10) as usize).min(n - 1);
+            let count = rng.range(1..6) as u64;
+            if contents[i].iter().any(|&(k, _)| k == j) {
+                continue;
+            }
+            if i < gold {
+                contents[i].push((j, count));
+            } else if inside[i] + count * (1 + inside[j]) <= MAX_INSIDE {
+                contents[i].push((j, count));
+                inside[i] += count * (1 + inside[j]);
+            }
+        }
+    }
+
+    // The containers of shiny gold are everything which can reach it.
+    let mut reaches = vec![false; n];
+    reaches[gold] = true;
+    for i in (0..gold).rev() {
+        reaches[i] = contents[i].iter().any(|&(j, _)| reaches[j]);
+    }
+    let num_containers = reaches.iter().filter(|&&r| r).count() - 1;
+
+    let mut lines: Vec<String> = (0..n)
+        .map(|i| {
+            let bags = if contents[i].is_empty() {
+                String::from("no other bags")
+            } else {
+                contents[i]
+                    .iter()
+                    .map(|&(j, count)| format!("{} {} bag{}", count, colors[j], if count == 1 { "" } else { "s" }))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            };
+            format!("{} bags contain {}.", colors[i], bags)
+        })
+        .collect();
+    rng.shuffle(&mut lines);
+    Generated {
+        input: lines.join("\n"),
+        part1: Some(num_containers.to_string()),
+        part2: Some(inside[gold].to_string()),
+        ..Generated::default()
+    }
+}
+
+pub struct Solver;
+
+pub struct Bags {
+    pub graph: BagGraph,
+    pub start: ColorId,
+}
+
+impl Solution for Solver {
+    type Input = Bags;
+    const DAY: u32 = 7;
+
+    /// The optional arg is `--bag COLOR`, the color of bag to ask about, which is
+    /// shiny gold by default.
+    fn parse(source: &Source, args: &[String]) -> Result<Bags, ParseError> {
+        check_flags(args, &["--bag"])?;
+        let color: String = parse_flag(args, "--bag", String::from("shiny gold"))?;
+        let graph = source.parse(parse_rules)?;
+        let start = graph
+            .id(&color)
+            .ok_or_else(|| ParseError::arg(&color, &format!("There's no rule for {} bags", color)))?;
+        Ok(Bags { graph, start })
+    }
+
+    fn part1(bags: &Bags) -> Option<String> {
+        let ancestors = bags.graph.ancestors(bags.start);
+        debug!("Containers: {:?}", ancestors.iter().map(|&id| bags.graph.name(id)).collect::<Vec<_>>());
+        Some(ancestors.len().to_string())
+    }
+
+    fn part2(bags: &Bags) -> Option<String> {
+        Some(bags.graph.bags_inside(bags.start).to_string())
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated> {
+        Some(generate_rules(rng, size))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+
+    #[test]
+    fn test_bag_graph() {
+        let graph = parse_rules(
+            "a b bags contain 2 c d bags, 1 e f bag.\n\
+             c d bags contain 3 e f bags.\n\
+             e f bags contain no other bags.\n\
+             g h bags contain 1 a b bag.",
+        )
+        .unwrap();
+        let names = |ids: Vec<ColorId>| ids.into_iter().map(|id| graph.name(id)).collect::<Vec<_>>();
+        let (ab, ef) = (graph.id("a b").unwrap(), graph.id("e f").unwrap());
+        assert_eq!(vec!["a b", "c d", "g h"], names(graph.ancestors(ef)));
+        assert_eq!(vec!["c d", "e f"], names(graph.descendants(ab)));
+        assert_eq!((9, 0, 10), (graph.bags_inside(ab), graph.bags_inside(ef), graph.bags_inside(graph.id("g h").unwrap())));
+        assert_eq!(None, graph.id("shiny gold"));
+
+        let err = parse_rules(
+            "a b bags contain 2 c d bags.\n\
+             c d bags contain 3 e f bags.\n\
+             e f bags contain 1 a b bag.",
+        )
+        .unwrap_err();
+        assert_eq!((1, 20, "Bags can't contain themselves: a b -> c d -> e f -> a b"), (err.line, err.column, err.message.as_str()));
+        let err = parse_rules("a b bags contain 2 c d bags.\nc d bags contain 1 c d bag.").unwrap_err();
+        assert_eq!((2, 20, "Bags can't contain themselves: c d -> c d"), (err.line, err.column, err.message.as_str()));
+        let err = parse_rules("x y bags contain 1 ax y bag, 1 x y bag.\nax y bags contain no other bags.").unwrap_err();
+        assert_eq!((1, 32), (err.line, err.column));
+        // The missing color is also the end of another color's name.
+        let err = parse_rules("x y bags contain 1 light red bag, 2 red bags.\nlight red bags contain no other bags.").unwrap_err();
+        assert_eq!((1, 37, "There's no rule for red bags"), (err.line, err.column, err.message.as_str()));
+    }
+}
@@ -0,0 +1,209 @@
+use crate::error::{parse_at, ParseError};
+use crate::generate::Generated;
+use crate::pool;
+use crate::rng::Rng;
+use crate::solution::Solution;
+use crate::util::{self, Source};
+use crate::{debug, info};
+use regex::Regex;
+use std::collections::HashSet;
+use std::fmt;
+
+// TODO: x Change Instruction.op to be more like a tagged union
+// TODO: x Use match / case on op.op
+// TODO: x Use if let in process_file
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum Op {
+    Nop(i32),
+    Acc(i32),
+    Jmp(i32),
+}
+
+lazy_static! {
+    static ref INSTR_RE: Regex = Regex::new(r"^([a-z]+) ([-+])([0-9]+)$").unwrap();
+}
+
+pub fn parse_instruction(text: &str) -> Result<Op, ParseError> {
+    let groups = INSTR_RE
+        .captures(text)
+        .ok_or_else(|| ParseError::whole(text, "Expected an instruction like 'acc +1'"))?;
+    let op = &groups[1];
+    let sgn = if &groups[2] == "-" { -1 } else { 1 };
+    let arg = sgn * parse_at::<i32>(text, &groups[3])?;
+
+    match op {
+        "nop" => Ok(Op::Nop(arg)),
+        "acc" => Ok(Op::Acc(arg)),
+        "jmp" => Ok(Op::Jmp(arg)),
+        _ => Err(ParseError::at(text, op, &format!("Unknown op: {}", op))),
+    }
+}
+
+pub fn parse_program(text: &str) -> Result<Vec<Op>, ParseError> {
+    util::parse_text_lines(text, parse_instruction)
+}
+
+pub fn read_program(path: &str) -> Result<Vec<Op>, ParseError> {
+    Source::read(path)?.parse(parse_program)
+}
+
+/// Returns the final accumulator value or None if the program goes into an infinite loop.
+pub fn run_program(ops: &[Op]) -> Option<i32> {
+    let mut line = 0usize;
+    let mut acc = 0;
+    let mut run_lines: HashSet<usize> = HashSet::new();
+
+    loop {
+        if run_lines.contains(&line) {
+            debug!("{} {}: {:?} acc {} (again)", run_lines.len(), line, &ops[line], acc);
+            return None;
+        } else if line == ops.len() {
+            return Some(acc);
+        }
+
+        let mut next_line = line + 1;
+        run_lines.insert(line);
+        let op = &ops[line];
+        debug!("{} {}: {:?} acc {}", run_lines.len(), line, op, acc);
+        match op {
+            Op::Nop(_arg) => {}
+            Op::Acc(arg) => {
+                acc += arg;
+            }
+            Op::Jmp(arg) => {
+                next_line = (line as i32 + arg) as usize;
+            }
+        }
+        line = next_line;
+    }
+}
+
+/// Find the one Nop/Jmp swap that makes the program terminate, and return its final
+/// accumulator value. The swaps are tried in parallel, but if more than one works,
+/// it's still the first one's value.
+pub fn fix_program(program: &[Op]) -> Option<i32> {
+    pool::find_first(program.len(), |i| {
+        let mut variation = program.to_vec();
+        variation[i] = match program[i] {
+            Op::Nop(arg) => Op::Jmp(arg),
+            Op::Jmp(arg) => Op::Nop(arg),
+            Op::Acc(arg) => Op::Acc(arg),
+        };
+        let acc = run_program(&variation)?;
+        info!("swap: {}, acc: {}", i, acc);
+        Some(acc)
+    })
+}
+
+impl fmt::Display for Op {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Op::Nop(arg) => write!(f, "nop {:+}", arg),
+            Op::Acc(arg) => write!(f, "acc {:+}", arg),
+            Op::Jmp(arg) => write!(f, "jmp {:+}", arg),
+        }
+    }
+}
+
+/// A random program of `size` instructions with exactly one corrupted `nop`, with the
+/// answer planted.
+///
+/// The program runs straight through a prefix of `acc`s, `nop +0`s and forward jumps
+/// until it hits the corrupted instruction, a `jmp` back into the prefix. Swapping
+/// any other instruction in the prefix either changes nothing or loops on the spot,
+/// and nothing after the corrupted instruction ever runs, so the only fix is to turn
+/// it back into a `nop`. After that comes a suffix which always runs off the end.
+pub fn generate_program(rng: &mut Rng, size: usize) -> Generated {
+    let n = size.max(4);
+    let k = rng.range(n as i64 / 3..n as i64 / 2 + 1).max(1) as usize;
+    let mut ops = vec![];
+    let mut ran = vec![];
+    let mut acc = 0;
+
+    // Skipped instructions in the prefix loop forever if a swap makes them run.
+    while ops.len() < k {
+        ran.push(ops.len());
+        match rng.below(4) {
+            0 | 1 => {
+                let arg = rng.range(-50..51) as i32;
+                acc += arg;
+                ops.push(Op::Acc(arg));
+            }
+            2 => ops.push(Op::Nop(0)),
+            _ => {
+                let skip = (rng.below(3) as usize).min(k - ops.len() - 1);
+                ops.push(Op::Jmp(skip as i32 + 1));
+                ops.extend((0..skip).map(|_| Op::Jmp(0)));
+            }
+        }
+    }
+    let target = *rng.choose(&ran);
+    ops.push(Op::Jmp(target as i32 - k as i32));
+
+    // The suffix can skip over anything, since it only ever runs in the fixed program.
+    while ops.len() < n {
+        let i = ops.len();
+        match rng.below(4) {
+            0 | 1 => {
+                let arg = rng.range(-50..51) as i32;
+                acc += arg;
+                ops.push(Op::Acc(arg));
+            }
+            2 => ops.push(Op::Nop(rng.range(-(i as i64)..(n - i) as i64 + 1) as i32)),
+            _ => {
+                let skip = (rng.below(4) as usize).min(n - i - 1);
+                ops.push(Op::Jmp(skip as i32 + 1));
+                for j in i + 1..i + 1 + skip {
+                    let arg = rng.range(-(j as i64)..(n - j) as i64 + 1) as i32;
+                    ops.push(match rng.below(3) {
+                        0 => Op::Acc(arg),
+                        1 => Op::Nop(arg),
+                        _ => Op::Jmp(arg),
+                    });
+                }
+            }
+        }
+    }
+
+    Generated {
+        input: ops.iter().map(|op| op.to_string()).collect::<Vec<_>>().join("\n"),
+        part2: Some(acc.to_string()),
+        ..Generated::default()
+    }
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    type Input = Vec<Op>;
+    const DAY: u32 = 8;
+
+    fn parse(source: &Source, _args: &[String]) -> Result<Vec<Op>, ParseError> {
+        source.parse(parse_program)
+    }
+
+    fn part2(program: &Vec<Op>) -> Option<String> {
+        fix_program(program).map(|acc| acc.to_string())
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated> {
+        Some(generate_program(rng, size))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+
+    #[test]
+    fn test_parse_instr() {
+        assert_eq!(parse_instruction("nop +0"), Ok(Op::Nop(0)));
+        assert_eq!(parse_instruction("jmp -4"), Ok(Op::Jmp(-4)));
+
+        let err = parse_instruction("nap +0").unwrap_err();
+        assert_eq!((1, 3), (err.column, err.len));
+        assert!(parse_instruction("acc 4").is_err());
+    }
+}
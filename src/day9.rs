@@ -0,0 +1,158 @@
+use itertools::Itertools;
+
+use crate::error::{parse_at, ParseError};
+use crate::generate::Generated;
+use crate::rng::Rng;
+use crate::solution::{parse_arg, Solution};
+use crate::util::{self, Source};
+use crate::info;
+
+pub fn is_pair_sum(n: u64, nums: &[u64]) -> bool {
+    nums.iter().tuple_combinations().any(|(a, b)| a + b == n)
+}
+
+pub fn parse_nums(text: &str) -> Result<Vec<u64>, ParseError> {
+    util::parse_text_lines(text, |line| parse_at(line, line))
+}
+
+pub fn parse_file(path: &str) -> Result<Vec<u64>, ParseError> {
+    Source::read(path)?.parse(parse_nums)
+}
+
+pub fn find_invalid(nums: &[u64], preamble_len: usize) -> Option<u64> {
+    nums
+        .iter()
+        .enumerate()
+        .dropping(preamble_len)
+        .find(|(i, &n)| !is_pair_sum(n, &nums[(*i as i32 - preamble_len as i32) as usize..*i]))
+        .map(|x| *x.1)
+
+    // for i in (preamble_len as usize)..nums.len() {
+    //     let n = nums[i];
+    //     if !is_pair_sum(n, &nums[(i as i32 - preamble_len) as usize..i]) {
+    //         println!("Invalid: {}", n);
+    //         return Some(n);
+    //     }
+    // }
+    // None
+}
+
+pub fn find_sequence(nums: &[u64], target: u64) -> u64 {
+    let n = nums.len() as i32;
+    let (a, b) = (2..n)
+        .find_map(|d| {
+            (0..=n - d)
+                .find(|&a| nums[a as usize..(a + d) as usize].iter().sum::<u64>() == target)
+                .map(|a| (a, a + d))
+        })
+        .unwrap();
+
+    let s = &nums[a as usize..b as usize];
+    let lo = s.iter().min().unwrap();
+    let hi = s.iter().max().unwrap();
+    info!(
+        "a: {}, b: {}, min: {}, max: {}, sum: {}",
+        a,
+        b,
+        lo,
+        hi,
+        lo + hi
+    );
+    lo + hi
+}
+
+/// About `size` numbers with one invalid number planted in the middle, and a short
+/// run before it which sums to it. The preamble grows with the size, since every
+/// valid number is at least twice the smallest one before it, and it's passed as
+/// an arg.
+pub fn generate_xmas(rng: &mut Rng, size: usize) -> Generated {
+    let n = size.clamp(12, 2000);
+    let preamble = (n / 40).max(5);
+    let k = rng.range((n / 2) as i64..(3 * n / 4) as i64) as usize;
+    let valid = |rng: &mut Rng, nums: &[u64]| {
+        let window = &nums[nums.len() - preamble..];
+        let i = rng.below(preamble as u64) as usize;
+        let j = (i + 1 + rng.below(preamble as u64 - 1) as usize) % preamble;
+        window[i] + window[j]
+    };
+
+    let mut nums: Vec<u64> = (0..preamble).map(|_| rng.range(1..50) as u64).collect();
+    while nums.len() < k {
+        let next = valid(rng, &nums);
+        nums.push(next);
+    }
+
+    // The run must be the first one the solver comes to: shortest, then earliest.
+    let (run, invalid) = loop {
+        let len = rng.range(2..6) as usize;
+        let start = rng.below((k - len) as u64) as usize;
+        let target: u64 = nums[start..start + len].iter().sum();
+        let first = (2..=len).find_map(|d| (0..=k - d).find(|&a| nums[a..a + d].iter().sum::<u64>() == target).map(|a| (a, d)));
+        if first == Some((start, len)) && !is_pair_sum(target, &nums[k - preamble..]) {
+            break (&nums[start..start + len], target);
+        }
+    };
+    let weakness = run.iter().min().unwrap() + run.iter().max().unwrap();
+
+    nums.push(invalid);
+    while nums.len() < n {
+        let next = valid(rng, &nums);
+        nums.push(next);
+    }
+    Generated {
+        input: nums.iter().map(|n| n.to_string()).join("\n"),
+        args: vec![preamble.to_string()],
+        part1: Some(invalid.to_string()),
+        part2: Some(weakness.to_string()),
+    }
+}
+
+pub struct Xmas {
+    pub nums: Vec<u64>,
+    pub preamble: usize,
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    type Input = Xmas;
+    const DAY: u32 = 9;
+
+    /// The optional arg is the preamble length, which defaults to 25.
+    fn parse(source: &Source, args: &[String]) -> Result<Xmas, ParseError> {
+        let preamble = parse_arg(args, 0, 25)?;
+        Ok(Xmas {
+            nums: source.parse(parse_nums)?,
+            preamble,
+        })
+    }
+
+    fn part1(xmas: &Xmas) -> Option<String> {
+        find_invalid(&xmas.nums, xmas.preamble).map(|n| n.to_string())
+    }
+
+    fn part2(xmas: &Xmas) -> Option<String> {
+        let invalid = find_invalid(&xmas.nums, xmas.preamble)?;
+        Some(find_sequence(&xmas.nums, invalid).to_string())
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<Generated> {
+        Some(generate_xmas(rng, size))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+
+    #[test]
+    fn test_find_invalid() {
+        let nums = vec![
+            35, 20, 15, 25, 47, 40, 62, 55, 65, 95, 102, 117, 150, 182, 127, 219, 299, 277,
+            309, 576,
+        ];
+        assert_eq!(find_invalid(&nums, 5), Some(127));
+        assert_eq!(find_sequence(&nums, 127), 15 + 47);
+    }
+}
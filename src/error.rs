@@ -0,0 +1,194 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use pest::error::{ErrorVariant, InputLocation};
+use pest::RuleType;
+
+/// A problem with a puzzle input, pointing at the offending text.
+///
+/// Line parsers report errors relative to the text they were given (line 1, or
+/// whichever line of a multi-line chunk was bad). The functions which read whole
+/// files then shift the line number and fill in the file name.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub file: String,
+    /// 1-based line number, or 0 if the error isn't about a particular line.
+    pub line: usize,
+    /// 1-based column of the first offending character.
+    pub column: usize,
+    /// Number of offending characters, for the caret underline.
+    pub len: usize,
+    /// The full text of the offending line.
+    pub text: String,
+    pub message: String,
+}
+
+impl ParseError {
+    /// An error spanning `len` characters starting at `column` (1-based) of `line`.
+    pub fn new(line: &str, column: usize, len: usize, message: &str) -> ParseError {
+        ParseError {
+            file: String::new(),
+            line: 1,
+            column,
+            len,
+            text: String::from(line),
+            message: String::from(message),
+        }
+    }
+
+    /// An error covering the whole of `line`.
+    pub fn whole(line: &str, message: &str) -> ParseError {
+        ParseError::new(line, 1, line.chars().count().max(1), message)
+    }
+
+    /// An error pointing at `part`, which should be a slice of `line` (e.g. a regex capture).
+    pub fn at(line: &str, part: &str, message: &str) -> ParseError {
+        let start = line.as_ptr() as usize;
+        let offset = part.as_ptr() as usize;
+        let byte_col = if offset >= start && offset + part.len() <= start + line.len() {
+            offset - start
+        } else {
+            line.find(part).unwrap_or(0)
+        };
+        let column = line[..byte_col].chars().count() + 1;
+        ParseError::new(line, column, part.chars().count().max(1), message)
+    }
+
+    /// An error from a pest parser for `text`, listing the rules it expected.
+    pub fn from_pest<R: RuleType>(text: &str, e: pest::error::Error<R>) -> ParseError {
+        let (start, end) = match e.location {
+            InputLocation::Pos(p) => (p, p + 1),
+            InputLocation::Span(span) => span,
+        };
+        let message = match e.variant {
+            ErrorVariant::ParsingError { positives, .. } if !positives.is_empty() => {
+                let expected: Vec<String> = positives.iter().map(|r| format!("{:?}", r)).collect();
+                format!("Expected {}", expected.join(" or "))
+            }
+            ErrorVariant::CustomError { message } => message,
+            _ => String::from("Unexpected input"),
+        };
+        let column = text[..start.min(text.len())].chars().count() + 1;
+        ParseError::new(text, column, end.saturating_sub(start).max(1), &message)
+    }
+
+    /// An error that isn't about any particular line, e.g. a missing file.
+    pub fn general(message: &str) -> ParseError {
+        ParseError {
+            file: String::new(),
+            line: 0,
+            column: 0,
+            len: 0,
+            text: String::new(),
+            message: String::from(message),
+        }
+    }
+
+    /// A bad command-line argument for a solution.
+    pub fn arg(arg: &str, message: &str) -> ParseError {
+        ParseError {
+            text: String::from(arg),
+            ..ParseError::general(message).in_file("<args>")
+        }
+    }
+
+    /// Move the error down by `lines`, for when the text it was reported against
+    /// started partway through a file.
+    pub fn shift(mut self, lines: usize) -> ParseError {
+        if self.line > 0 {
+            self.line += lines;
+        }
+        self
+    }
+
+    /// Record which file the error came from, unless that's already known.
+    pub fn in_file(mut self, file: &str) -> ParseError {
+        if self.file.is_empty() {
+            self.file = String::from(file);
+        }
+        self
+    }
+
+    /// A rustc-style report with the offending text underlined, e.g.
+    ///
+    /// ```text
+    /// error: Unknown op: nap
+    ///  --> inputs/day8.txt:3:1
+    ///   |
+    /// 3 | nap +0
+    ///   | ^^^
+    /// ```
+    pub fn diagnostic(&self) -> String {
+        let file = if self.file.is_empty() { "<input>" } else { &self.file };
+        if self.line == 0 {
+            return format!("error: {}\n --> {}", self.message, file);
+        }
+        let gutter = " ".repeat(self.line.to_string().len());
+        format!(
+            "error: {}\n{}--> {}:{}:{}\n{} |\n{} | {}\n{} | {}{}",
+            self.message,
+            gutter,
+            file,
+            self.line,
+            self.column,
+            gutter,
+            self.line,
+            self.text,
+            gutter,
+            " ".repeat(self.column.saturating_sub(1)),
+            "^".repeat(self.len.max(1)),
+        )
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let file = if self.file.is_empty() { "<input>" } else { &self.file };
+        if self.line == 0 {
+            write!(f, "{}: {}", file, self.message)
+        } else {
+            write!(f, "{}:{}:{}: {}", file, self.line, self.column, self.message)
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+/// Parse `part`, a slice of `line`, reporting an error at its position if it's invalid.
+pub fn parse_at<T: FromStr>(line: &str, part: &str) -> Result<T, ParseError> {
+    part.trim()
+        .parse::<T>()
+        .map_err(|_| ParseError::at(line, part, &format!("Invalid value: '{}'", part)))
+}
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+
+    #[test]
+    fn test_at() {
+        let line = "acc +x1";
+        let err = ParseError::at(line, &line[4..], "bad");
+        assert_eq!(5, err.column);
+        assert_eq!(3, err.len);
+
+        let err = err.shift(2).in_file("day8.txt");
+        assert_eq!(3, err.line);
+        assert_eq!("day8.txt:3:5: bad", err.to_string());
+        assert_eq!(
+            "error: bad\n --> day8.txt:3:5\n  |\n3 | acc +x1\n  |     ^^^",
+            err.diagnostic()
+        );
+    }
+
+    #[test]
+    fn test_parse_at() {
+        let line = "mem[8] = 1x";
+        assert_eq!(Ok(8), parse_at::<u64>(line, &line[4..5]));
+        let err = parse_at::<u64>(line, &line[9..]).unwrap_err();
+        assert_eq!(10, err.column);
+        assert_eq!("Invalid value: '1x'", err.message);
+    }
+}
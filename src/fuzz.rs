@@ -0,0 +1,364 @@
+//! An offline mutation fuzzer for the input parsers.
+//!
+//! Each target is a parser which should return an error for bad input rather than
+//! panic. The fuzzer starts from a corpus cut out of the real and sample inputs
+//! (`inputs/dayN*.txt`), mutates it with a seeded `Rng` and checks that the parser
+//! (and the diagnostic for any error it returns) never panics. It's no substitute
+//! for coverage-guided fuzzing, but it needs no extra tools and every crash can be
+//! replayed from its seed.
+
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::error::ParseError;
+use crate::rng::Rng;
+use crate::solution::Solution;
+use crate::util::{self, Source};
+use crate::{
+    day1, day10, day11, day12, day13, day14, day15, day16, day17, day18, day19, day2, day20,
+    day21, day22, day23, day24, day25, day3, day4, day5, day6, day7, day8, day9,
+};
+
+/// How a corpus file is cut up into inputs for a target.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Unit {
+    Line,
+    Paragraph,
+    File,
+}
+
+pub struct Target {
+    pub name: &'static str,
+    pub day: u32,
+    pub unit: Unit,
+    pub run: fn(&str),
+}
+
+/// Render any error, since a bad column or length could panic there too.
+fn check<T>(result: Result<T, ParseError>) {
+    if let Err(e) = result {
+        e.diagnostic();
+    }
+}
+
+/// Parse a whole input the way the runner does, for the days without a target of their own.
+fn parse_input<S: Solution>(text: &str) {
+    check(S::parse(&Source::new("<fuzz>", text), &[]))
+}
+
+pub fn targets() -> Vec<Target> {
+    vec![
+        Target {
+            name: "day1::Solver::parse",
+            day: 1,
+            unit: Unit::File,
+            run: parse_input::<day1::Solver>,
+        },
+        Target {
+            name: "day2::read_password",
+            day: 2,
+            unit: Unit::Line,
+            run: |s| check(day2::read_password(s)),
+        },
+        Target {
+            name: "day3::Solver::parse",
+            day: 3,
+            unit: Unit::File,
+            run: parse_input::<day3::Solver>,
+        },
+        Target {
+            name: "day4::parse_passports",
+            day: 4,
+            unit: Unit::Paragraph,
+            run: |s| {
+                let checked = day4::parse_passports(s).map(|passports| {
+                    for passport in passports.iter() {
+                        day4::check_passport(passport);
+                    }
+                });
+                check(checked)
+            },
+        },
+        Target {
+            name: "day5::Solver::parse",
+            day: 5,
+            unit: Unit::File,
+            run: parse_input::<day5::Solver>,
+        },
+        Target {
+            name: "day6::parse_groups",
+            day: 6,
+            unit: Unit::Paragraph,
+            run: |s| check(day6::parse_groups(s)),
+        },
+        Target {
+            name: "day7::parse_rules",
+            day: 7,
+            unit: Unit::File,
+            run: |s| check(day7::parse_rules(s)),
+        },
+        Target {
+            name: "day8::Solver::parse",
+            day: 8,
+            unit: Unit::File,
+            run: parse_input::<day8::Solver>,
+        },
+        Target {
+            name: "day9::Solver::parse",
+            day: 9,
+            unit: Unit::File,
+            run: parse_input::<day9::Solver>,
+        },
+        Target {
+            name: "day10::Solver::parse",
+            day: 10,
+            unit: Unit::File,
+            run: parse_input::<day10::Solver>,
+        },
+        Target {
+            name: "day11::Solver::parse",
+            day: 11,
+            unit: Unit::File,
+            run: parse_input::<day11::Solver>,
+        },
+        Target {
+            name: "day12::Solver::parse",
+            day: 12,
+            unit: Unit::File,
+            run: parse_input::<day12::Solver>,
+        },
+        Target {
+            name: "day13::Solver::parse",
+            day: 13,
+            unit: Unit::File,
+            run: parse_input::<day13::Solver>,
+        },
+        Target {
+            name: "day14::Solver::parse",
+            day: 14,
+            unit: Unit::File,
+            run: parse_input::<day14::Solver>,
+        },
+        Target {
+            name: "day15::Solver::parse",
+            day: 15,
+            unit: Unit::File,
+            run: parse_input::<day15::Solver>,
+        },
+        Target {
+            name: "day16::parse_rule",
+            day: 16,
+            unit: Unit::Line,
+            run: |s| check(day16::parse_rule(s)),
+        },
+        Target {
+            name: "day17::Solver::parse",
+            day: 17,
+            unit: Unit::File,
+            run: parse_input::<day17::Solver>,
+        },
+        Target {
+            name: "day18::Solver::parse",
+            day: 18,
+            unit: Unit::File,
+            run: parse_input::<day18::Solver>,
+        },
+        Target {
+            name: "day19::parse_rule",
+            day: 19,
+            unit: Unit::Line,
+            run: |s| check(day19::parse_rule(s)),
+        },
+        Target {
+            name: "day20::Solver::parse",
+            day: 20,
+            unit: Unit::File,
+            run: parse_input::<day20::Solver>,
+        },
+        Target {
+            name: "day21::parse_recipe",
+            day: 21,
+            unit: Unit::Line,
+            run: |s| check(day21::parse_recipe(s)),
+        },
+        Target {
+            name: "day22::Solver::parse",
+            day: 22,
+            unit: Unit::File,
+            run: parse_input::<day22::Solver>,
+        },
+        Target {
+            name: "day23::Solver::parse",
+            day: 23,
+            unit: Unit::File,
+            run: parse_input::<day23::Solver>,
+        },
+        Target {
+            name: "day24::parse_line",
+            day: 24,
+            unit: Unit::Line,
+            run: |s| check(day24::parse_line(s)),
+        },
+        Target {
+            name: "day25::Solver::parse",
+            day: 25,
+            unit: Unit::File,
+            run: parse_input::<day25::Solver>,
+        },
+    ]
+}
+
+/// The target called `name`, or the only one for a day if `name` is a number.
+pub fn find_target(name: &str) -> Option<Target> {
+    let day = name.parse::<u32>().ok();
+    targets().into_iter().find(|t| t.name == name || Some(t.day) == day)
+}
+
+/// The seed inputs for `target`: every line, paragraph or whole file of the inputs
+/// for its day in `dir`.
+pub fn corpus(target: &Target, dir: &str) -> Result<Vec<String>, ParseError> {
+    let prefix = format!("day{}.", target.day);
+    let mut paths: Vec<String> = fs::read_dir(dir)
+        .map_err(|e| ParseError::general(&format!("Unable to read corpus: {}", e)).in_file(dir))?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .filter(|name| name.starts_with(&prefix) && name.ends_with(".txt"))
+        .map(|name| format!("{}/{}", dir, name))
+        .collect();
+    paths.sort();
+
+    let mut inputs = vec![];
+    for path in paths {
+        let source = Source::read(&path)?;
+        match target.unit {
+            Unit::Line => inputs.extend(source.lines().filter(|l| !l.is_empty()).map(String::from)),
+            Unit::Paragraph => inputs.extend(util::paragraphs(source.text()).iter().map(|(_, p)| p.to_string())),
+            Unit::File => inputs.push(source.text().to_string()),
+        }
+    }
+    inputs.dedup();
+    Ok(inputs)
+}
+
+/// Characters which tend to matter to the parsers, plus a few that don't fit anywhere.
+const ALPHABET: &[char] = &[
+    '0', '1', '9', ' ', ':', '-', ',', '|', '"', '(', ')', '#', '\n', '\r', '\t', 'a', 'e', 'n', 's',
+    'w', 'z', 'é', '日', '\u{feff}',
+];
+
+/// Strings which tend to break number parsing and slicing.
+const TOKENS: &[&str] = &["0", "-1", "4294967296", "99999999999999999999", "  ", "bags", "contain", " or "];
+
+/// Apply a few random edits to `input`, sometimes splicing in part of `other`. The
+/// result is always valid UTF-8, since the parsers take `&str`.
+pub fn mutate(rng: &mut Rng, input: &str, other: &str) -> String {
+    let mut cs: Vec<char> = input.chars().collect();
+    for _ in 0..rng.range(1..5) {
+        let i = rng.below(cs.len() as u64 + 1) as usize;
+        let j = (i + rng.range(1..4) as usize).min(cs.len());
+        match rng.below(7) {
+            0 => {
+                cs.drain(i..j);
+            }
+            1 => cs.insert(i, *rng.choose(ALPHABET)),
+            2 => {
+                let token: Vec<char> = rng.choose(TOKENS).chars().collect();
+                cs.splice(i..i, token);
+            }
+            3 if i < cs.len() => cs[i] = *rng.choose(ALPHABET),
+            4 => {
+                let span = cs[i..j].to_vec();
+                cs.splice(j..j, span);
+            }
+            5 => cs.truncate(i),
+            _ => {
+                let other: Vec<char> = other.chars().collect();
+                let k = rng.below(other.len() as u64 + 1) as usize;
+                cs.truncate(i);
+                cs.extend_from_slice(&other[k..]);
+            }
+        }
+    }
+    cs.into_iter().collect()
+}
+
+/// An input which made a target panic.
+#[derive(Debug, Clone)]
+pub struct Crash {
+    pub target: &'static str,
+    /// The seed of the case, or None for an unmodified corpus input.
+    pub seed: Option<u64>,
+    pub input: String,
+    pub message: String,
+}
+
+/// Run `target` on each input in `corpus` and then on `cases` mutations of them,
+/// seeded from `seed`. Returns the number of inputs tried, or the first crash.
+/// The panic hook still reports each caught panic, so callers that want quiet output
+/// should replace it.
+pub fn fuzz(target: &Target, corpus: &[String], cases: u64, seed: u64) -> Result<u64, Crash> {
+    let try_input = |input: &str, seed: Option<u64>| {
+        panic::catch_unwind(AssertUnwindSafe(|| (target.run)(input))).map_err(|payload| {
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| String::from("(no message)"));
+            Crash {
+                target: target.name,
+                seed,
+                input: String::from(input),
+                message,
+            }
+        })
+    };
+
+    corpus
+        .iter()
+        .try_for_each(|input| try_input(input, None))
+        .and_then(|_| {
+            (seed..seed + cases).try_for_each(|s| try_input(&replay(corpus, s), Some(s)))
+        })
+        .map(|_| corpus.len() as u64 + cases)
+}
+
+/// The input for the case with `seed`.
+pub fn replay(corpus: &[String], seed: u64) -> String {
+    let mut rng = Rng::new(seed);
+    if corpus.is_empty() {
+        return mutate(&mut rng, "", "");
+    }
+    let input = rng.choose(corpus);
+    let other = rng.choose(corpus);
+    mutate(&mut rng, input, other)
+}
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+
+    #[test]
+    fn test_mutate() {
+        let corpus = vec![String::from("1-3 a: abcde"), String::from("2-9 c: ccccccccc")];
+        assert_eq!(replay(&corpus, 12), replay(&corpus, 12));
+        let mutants: Vec<String> = (0..50).map(|s| replay(&corpus, s)).collect();
+        assert!(mutants.iter().any(|m| !corpus.contains(m)));
+    }
+
+    #[test]
+    fn test_fuzz() {
+        let target = Target {
+            name: "fragile",
+            day: 0,
+            unit: Unit::Line,
+            run: |s| assert!(!s.contains(':'), "found a colon"),
+        };
+        let crash = fuzz(&target, &[String::from("ab")], 1000, 0).unwrap_err();
+        assert_eq!("found a colon", crash.message);
+        assert_eq!(crash.input, replay(&[String::from("ab")], crash.seed.unwrap()));
+
+        let sturdy = Target { run: |_| {}, ..target };
+        assert_eq!(Some(11), fuzz(&sturdy, &[String::from("ab")], 10, 0).ok());
+    }
+}
@@ -0,0 +1,96 @@
+//! Random puzzle inputs, for benchmarks and tests at scales the real inputs don't reach.
+//!
+//! Each day's `Solution::generate` makes a valid input of roughly `size` records
+//! (lines, groups, tiles, ...) from a seeded `Rng`. When the generator knows the
+//! answers by construction, it plants them in the `Generated` so that the solvers
+//! can be checked against them.
+
+use crate::rng::Rng;
+
+/// A generated puzzle input.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Generated {
+    pub input: String,
+    /// Extra args the solution needs for this input, e.g. day 9's preamble length.
+    pub args: Vec<String>,
+    /// The answers, if they're known by construction.
+    pub part1: Option<String>,
+    pub part2: Option<String>,
+}
+
+impl Generated {
+    /// An input whose answers aren't known.
+    pub fn new(input: String) -> Generated {
+        Generated {
+            input,
+            ..Generated::default()
+        }
+    }
+}
+
+/// Adjectives and colors, which pair up to name day 7's bags.
+const SHADES: [&str; 30] = [
+    "bright", "clear", "dark", "dim", "dotted", "drab", "dull", "faded", "light", "mirrored",
+    "muted", "pale", "plaid", "posh", "shiny", "striped", "vibrant", "wavy", "matte", "gloomy",
+    "glossy", "hazy", "rusty", "sooty", "frosted", "speckled", "dusty", "neon", "misty", "soft",
+];
+const COLORS: [&str; 30] = [
+    "aqua", "beige", "black", "blue", "bronze", "brown", "chartreuse", "coral", "crimson", "cyan",
+    "fuchsia", "gold", "gray", "green", "indigo", "lavender", "lime", "magenta", "maroon", "olive",
+    "orange", "plum", "purple", "red", "salmon", "silver", "tan", "teal", "tomato", "violet",
+];
+
+/// `n` distinct two-word color names, in random order. There are 900 in all.
+pub fn color_names(rng: &mut Rng, n: usize) -> Vec<String> {
+    let mut names: Vec<String> = SHADES
+        .iter()
+        .flat_map(|s| COLORS.iter().map(move |c| format!("{} {}", s, c)))
+        .collect();
+    rng.shuffle(&mut names);
+    names.truncate(n);
+    names
+}
+
+/// `n` distinct lowercase words of 3 to 8 letters, e.g. ingredients for day 21.
+pub fn words(rng: &mut Rng, n: usize) -> Vec<String> {
+    let mut out: Vec<String> = vec![];
+    while out.len() < n {
+        let len = rng.range(3..9);
+        let word: String = (0..len).map(|_| (b'a' + rng.below(26) as u8) as char).collect();
+        if !out.contains(&word) {
+            out.push(word);
+        }
+    }
+    out
+}
+
+/// The primes below `n`.
+pub fn primes_below(n: u64) -> Vec<u64> {
+    let mut is_prime = vec![true; n as usize];
+    let mut primes = vec![];
+    for i in 2..n as usize {
+        if is_prime[i] {
+            primes.push(i as u64);
+            for j in (i * i..n as usize).step_by(i) {
+                is_prime[j] = false;
+            }
+        }
+    }
+    primes
+}
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+
+    #[test]
+    fn test_helpers() {
+        assert_eq!(vec![2, 3, 5, 7, 11, 13], primes_below(17));
+        let names = color_names(&mut Rng::new(0), 900);
+        assert_eq!(900, names.len());
+        assert!(names.contains(&String::from("shiny gold")));
+        let ws = words(&mut Rng::new(0), 50);
+        assert!(ws.iter().all(|w| (3..=8).contains(&w.len()) && w.chars().all(|c| c.is_ascii_lowercase())));
+    }
+}
@@ -0,0 +1,367 @@
+//! A rectangular grid of cells, as found in many puzzle inputs.
+
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+use crate::error::ParseError;
+
+/// Offsets to the four orthogonal neighbors of a cell.
+pub const N4: [(i64, i64); 4] = [(0, -1), (-1, 0), (1, 0), (0, 1)];
+
+/// Offsets to all eight neighbors of a cell, diagonals included.
+pub const N8: [(i64, i64); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+/// What happens when you step off the edge of the grid.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Edges {
+    /// There's nothing there.
+    Clip,
+    /// You come back on the opposite side, as if the grid repeats forever.
+    Wrap,
+}
+
+/// A `width` × `height` grid, indexed by `(x, y)` with `(0, 0)` at the top left.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    /// Row-major.
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// A grid with each cell set by calling `f(x, y)`.
+    pub fn from_fn<F: FnMut(usize, usize) -> T>(width: usize, height: usize, mut f: F) -> Grid<T> {
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| f(x, y))
+            .collect();
+        Grid { width, height, cells }
+    }
+
+    /// A grid from its rows, which must all be the same length.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Grid<T> {
+        let height = rows.len();
+        let width = rows.first().map_or(0, |row| row.len());
+        assert!(rows.iter().all(|row| row.len() == width), "Rows must all be the same length");
+        Grid {
+            width,
+            height,
+            cells: rows.into_iter().flatten().collect(),
+        }
+    }
+
+    /// Parse a grid with one character per cell, mapping each with `f`. Leading and
+    /// trailing whitespace on each line is ignored, as are trailing blank lines.
+    pub fn parse<F: FnMut(char) -> Option<T>>(text: &str, mut f: F) -> Result<Grid<T>, ParseError> {
+        let mut cells = vec![];
+        let mut width = None;
+        let mut height = 0;
+        for (y, line) in text.trim_end().lines().enumerate() {
+            let row = line.trim();
+            for (i, c) in row.char_indices() {
+                match f(c) {
+                    Some(cell) => cells.push(cell),
+                    None => {
+                        let msg = format!("Invalid cell: {}", c);
+                        return Err(ParseError::at(line, &row[i..i + c.len_utf8()], &msg).shift(y));
+                    }
+                }
+            }
+            let len = row.chars().count();
+            match width {
+                None => width = Some(len),
+                Some(w) if w != len => {
+                    let msg = format!("Expected {} cells, got {}", w, len);
+                    return Err(ParseError::at(line, row, &msg).shift(y));
+                }
+                _ => {}
+            }
+            height += 1;
+        }
+
+        match width {
+            Some(width) if width > 0 => Ok(Grid { width, height, cells }),
+            _ => Err(ParseError::general("The grid is empty")),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The cell at `(x, y)`, if it's in the grid.
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        if x < self.width && y < self.height {
+            Some(&self.cells[y * self.width + x])
+        } else {
+            None
+        }
+    }
+
+    /// The cell at `(x, y)`, which may be off the grid. An empty grid has no cells to
+    /// wrap around to.
+    pub fn at(&self, x: i64, y: i64, edges: Edges) -> Option<&T> {
+        match edges {
+            Edges::Clip if x < 0 || y < 0 => None,
+            Edges::Clip => self.get(x as usize, y as usize),
+            Edges::Wrap => self.get(
+                x.checked_rem_euclid(self.width as i64)? as usize,
+                y.checked_rem_euclid(self.height as i64)? as usize,
+            ),
+        }
+    }
+
+    /// The cells at each offset from `(x, y)`, with their positions.
+    pub fn neighbors<'a>(
+        &'a self,
+        x: usize,
+        y: usize,
+        offsets: &'a [(i64, i64)],
+        edges: Edges,
+    ) -> impl Iterator<Item = ((usize, usize), &'a T)> + 'a {
+        offsets.iter().filter_map(move |&(dx, dy)| {
+            let (nx, ny) = (x as i64 + dx, y as i64 + dy);
+            let (nx, ny) = match edges {
+                Edges::Clip => (nx, ny),
+                Edges::Wrap => (nx.checked_rem_euclid(self.width as i64)?, ny.checked_rem_euclid(self.height as i64)?),
+            };
+            self.at(nx, ny, Edges::Clip).map(|cell| ((nx as usize, ny as usize), cell))
+        })
+    }
+
+    /// The four orthogonal neighbors of `(x, y)`.
+    pub fn neighbors4(&self, x: usize, y: usize, edges: Edges) -> impl Iterator<Item = ((usize, usize), &T)> {
+        self.neighbors(x, y, &N4, edges)
+    }
+
+    /// All eight neighbors of `(x, y)`.
+    pub fn neighbors8(&self, x: usize, y: usize, edges: Edges) -> impl Iterator<Item = ((usize, usize), &T)> {
+        self.neighbors(x, y, &N8, edges)
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.height).map(move |y| self.row(y))
+    }
+
+    /// The cells in column `x`, which is empty if `x` is off the grid.
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        (0..self.height).filter_map(move |y| self.get(x, y))
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(move |x| self.column(x))
+    }
+
+    /// Every cell with its position, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        let width = self.width;
+        self.cells.iter().enumerate().map(move |(i, cell)| ((i % width, i / width), cell))
+    }
+
+    pub fn map<U, F: FnMut(&T) -> U>(&self, f: F) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    /// Render the grid with one character per cell.
+    pub fn render<F: FnMut(&T) -> char>(&self, mut f: F) -> String {
+        self.rows()
+            .map(|row| row.iter().map(&mut f).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Grid<T> {
+        Grid {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    /// Rotate a quarter turn clockwise.
+    pub fn rot90(&self) -> Grid<T> {
+        Grid::from_fn(self.height, self.width, |x, y| self[(y, self.height - 1 - x)].clone())
+    }
+
+    pub fn rot180(&self) -> Grid<T> {
+        Grid::from_fn(self.width, self.height, |x, y| {
+            self[(self.width - 1 - x, self.height - 1 - y)].clone()
+        })
+    }
+
+    /// Rotate a quarter turn anticlockwise.
+    pub fn rot270(&self) -> Grid<T> {
+        Grid::from_fn(self.height, self.width, |x, y| self[(self.width - 1 - y, x)].clone())
+    }
+
+    /// Flip top to bottom.
+    pub fn flip_vert(&self) -> Grid<T> {
+        Grid::from_fn(self.width, self.height, |x, y| self[(x, self.height - 1 - y)].clone())
+    }
+
+    /// Flip left to right.
+    pub fn flip_horiz(&self) -> Grid<T> {
+        Grid::from_fn(self.width, self.height, |x, y| self[(self.width - 1 - x, y)].clone())
+    }
+
+    /// Flip about the diagonal from top left to bottom right.
+    pub fn transpose(&self) -> Grid<T> {
+        Grid::from_fn(self.height, self.width, |x, y| self[(y, x)].clone())
+    }
+
+    /// Flip about the diagonal from bottom left to top right.
+    pub fn anti_transpose(&self) -> Grid<T> {
+        Grid::from_fn(self.height, self.width, |x, y| {
+            self[(self.width - 1 - y, self.height - 1 - x)].clone()
+        })
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (usize, usize)) -> &T {
+        assert!(x < self.width && y < self.height, "({}, {}) is off the grid", x, y);
+        &self.cells[y * self.width + x]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut T {
+        assert!(x < self.width && y < self.height, "({}, {}) is off the grid", x, y);
+        &mut self.cells[y * self.width + x]
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+    use crate::rng::{for_all, Rng};
+
+    fn parse(text: &str) -> Grid<char> {
+        Grid::parse(text, |c| if c == '.' || c == '#' { Some(c) } else { None }).unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let g = parse(
+            "#..
+             ##.
+             \n",
+        );
+        assert_eq!((3, 2), (g.width(), g.height()));
+        assert_eq!(Some(&'#'), g.get(1, 1));
+        assert_eq!(None, g.get(3, 0));
+        assert_eq!("#..\n##.", g.to_string());
+        assert_eq!(vec!['#', '#'], g.column(0).copied().collect::<Vec<_>>());
+
+        let err = Grid::parse("..\n.x", |c| if c == '.' { Some(()) } else { None }).unwrap_err();
+        assert_eq!((2, 2, "Invalid cell: x"), (err.line, err.column, err.message.as_str()));
+        let err = Grid::parse("..\n  ...", Some).unwrap_err();
+        assert_eq!((2, 3, "Expected 2 cells, got 3"), (err.line, err.column, err.message.as_str()));
+        assert!(Grid::parse("\n\n", Some).is_err());
+    }
+
+    #[test]
+    fn test_access() {
+        let g = parse("#..\n.#.");
+        assert_eq!(None, g.at(-1, 0, Edges::Clip));
+        assert_eq!(Some(&'.'), g.at(-1, 0, Edges::Wrap));
+        assert_eq!(Some(&'#'), g.at(4, 3, Edges::Wrap));
+
+        let n4: Vec<_> = g.neighbors4(0, 0, Edges::Clip).map(|(p, _)| p).collect();
+        assert_eq!(vec![(1, 0), (0, 1)], n4);
+        assert_eq!(3, g.neighbors8(0, 0, Edges::Clip).count());
+        assert_eq!(8, g.neighbors8(0, 0, Edges::Wrap).count());
+        assert_eq!(2, g.neighbors8(0, 0, Edges::Wrap).filter(|(_, &c)| c == '#').count());
+    }
+
+    #[test]
+    fn test_empty() {
+        for g in [Grid::from_rows(vec![]), Grid::new(0, 2, '.'), Grid::from_fn(2, 0, |_, _| '.')].iter() {
+            assert_eq!(None, g.at(1, 1, Edges::Wrap));
+            assert_eq!(0, g.neighbors8(0, 0, Edges::Wrap).count());
+            assert_eq!(0, g.column(0).count());
+            assert!(g.rows().all(|row| row.is_empty()));
+            assert_eq!(g.rot90().rot270(), *g);
+        }
+        assert_eq!("\n", Grid::new(0, 2, '.').to_string());
+        assert_eq!("", Grid::<char>::from_rows(vec![]).to_string());
+    }
+
+    #[test]
+    fn test_transforms() {
+        let g = parse(
+            "#.#
+             #..",
+        );
+        assert_eq!("##\n..\n.#", g.rot90().to_string());
+        assert_eq!("..#\n#.#", g.rot180().to_string());
+        assert_eq!("#.\n..\n##", g.rot270().to_string());
+        assert_eq!("#..\n#.#", g.flip_vert().to_string());
+        assert_eq!("#.#\n..#", g.flip_horiz().to_string());
+        assert_eq!("##\n..\n#.", g.transpose().to_string());
+        assert_eq!(".#\n..\n##", g.anti_transpose().to_string());
+        assert_eq!(g, g.rot90().rot90().rot90().rot90());
+        assert_eq!(g.rot270(), g.rot90().rot180());
+        assert_eq!(g.transpose(), g.rot90().flip_horiz());
+        assert_eq!(g.anti_transpose(), g.rot90().flip_vert());
+    }
+
+    fn random_grid(rng: &mut Rng) -> Grid<bool> {
+        let (w, h) = (rng.range(1..7) as usize, rng.range(1..7) as usize);
+        Grid::from_fn(w, h, |_, _| rng.bool())
+    }
+
+    #[test]
+    fn test_transform_props() {
+        for_all(200, random_grid, |g| g.rot90().rot90().rot90().rot90() == *g);
+        for_all(200, random_grid, |g| g.rot90().rot270() == *g && g.rot90().rot90() == g.rot180());
+        for_all(200, random_grid, |g| g.transpose().transpose() == *g);
+        for_all(200, random_grid, |g| g.anti_transpose() == g.rot180().transpose());
+        for_all(200, random_grid, |g| g.flip_vert().flip_horiz() == g.rot180());
+        for_all(200, random_grid, |g| {
+            let r = g.rot90();
+            (r.width(), r.height()) == (g.height(), g.width())
+        });
+    }
+}
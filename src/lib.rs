@@ -1 +1,45 @@
+#[macro_use]
+extern crate lazy_static;
+#[macro_use]
+extern crate pest_derive;
+
+pub mod alloc;
+pub mod bench;
+pub mod error;
+pub mod fuzz;
+pub mod generate;
+pub mod grid;
+pub mod life;
+pub mod log;
+pub mod manifest;
+pub mod pool;
+pub mod rng;
+pub mod schema;
 pub mod util;
+pub mod solution;
+
+pub mod day1;
+pub mod day2;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod day9;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day14;
+pub mod day15;
+pub mod day16;
+pub mod day17;
+pub mod day18;
+pub mod day19;
+pub mod day20;
+pub mod day21;
+pub mod day22;
+pub mod day23;
+pub mod day24;
+pub mod day25;
@@ -0,0 +1,275 @@
+//! Conway-style cellular automata on an infinite grid.
+//!
+//! Only the active cells are stored, so the grid can grow without bound. The
+//! shape of the grid (which cells are neighbors) is a `Topology`, and the rules
+//! for which cells are born or survive are data, written like `B3/S23`.
+
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+use std::marker::PhantomData;
+
+use crate::error::{parse_at, ParseError};
+
+/// Which cells are next to which.
+pub trait Topology {
+    type Cell: Copy + Eq + Hash;
+
+    fn neighbors(&self, cell: &Self::Cell) -> Vec<Self::Cell>;
+}
+
+/// A square grid in `N` dimensions, where every cell within one step on each axis
+/// (diagonals included) is a neighbor.
+pub struct Square<const N: usize> {
+    offsets: Vec<[i32; N]>,
+}
+
+impl<const N: usize> Square<N> {
+    pub fn new() -> Square<N> {
+        let mut offsets = vec![[0; N]];
+        for axis in 0..N {
+            offsets = offsets
+                .iter()
+                .flat_map(|o| (-1..=1).map(move |d| {
+                    let mut o = *o;
+                    o[axis] = d;
+                    o
+                }))
+                .collect();
+        }
+        offsets.retain(|o| o.iter().any(|&d| d != 0));
+        Square { offsets }
+    }
+}
+
+impl<const N: usize> Default for Square<N> {
+    fn default() -> Square<N> {
+        Square::new()
+    }
+}
+
+impl<const N: usize> Topology for Square<N> {
+    type Cell = [i32; N];
+
+    fn neighbors(&self, cell: &[i32; N]) -> Vec<[i32; N]> {
+        self.offsets
+            .iter()
+            .map(|o| {
+                let mut n = *cell;
+                for (c, d) in n.iter_mut().zip(o.iter()) {
+                    *c += d;
+                }
+                n
+            })
+            .collect()
+    }
+}
+
+/// A grid of hexagons with flat rows, in `(x, y)` offset coordinates where the
+/// even rows are shifted half a tile to the right of the odd ones.
+pub struct Hex;
+
+impl Topology for Hex {
+    type Cell = (i32, i32);
+
+    /// The neighbors to the east, southeast, southwest, west, northwest and northeast.
+    fn neighbors(&self, &(x, y): &(i32, i32)) -> Vec<(i32, i32)> {
+        // The tiles above and below are either up and left or up and right.
+        let (l, r) = if y.abs() % 2 == 1 { (x - 1, x) } else { (x, x + 1) };
+        vec![(x + 1, y), (r, y + 1), (l, y + 1), (x - 1, y), (l, y - 1), (r, y - 1)]
+    }
+}
+
+/// Any other shape, given by a function from a cell to its neighbors.
+pub struct Custom<C, F> {
+    neighbors: F,
+    cell: PhantomData<C>,
+}
+
+impl<C, F: Fn(&C) -> Vec<C>> Custom<C, F> {
+    pub fn new(neighbors: F) -> Custom<C, F> {
+        Custom {
+            neighbors,
+            cell: PhantomData,
+        }
+    }
+}
+
+impl<C: Copy + Eq + Hash, F: Fn(&C) -> Vec<C>> Topology for Custom<C, F> {
+    type Cell = C;
+
+    fn neighbors(&self, cell: &C) -> Vec<C> {
+        (self.neighbors)(cell)
+    }
+}
+
+/// How many active neighbors it takes for an inactive cell to become active
+/// (birth), or for an active cell to stay active (survival).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rules {
+    pub birth: Vec<usize>,
+    pub survival: Vec<usize>,
+}
+
+impl Rules {
+    /// Conway's Game of Life, `B3/S23`.
+    pub fn conway() -> Rules {
+        Rules {
+            birth: vec![3],
+            survival: vec![2, 3],
+        }
+    }
+
+    /// Parse rules like `B3/S23`. Each digit is a count, unless the counts are
+    /// separated by commas (`B3/S2,3,10`), which allows counts over 9.
+    pub fn parse(text: &str) -> Result<Rules, ParseError> {
+        let (b, s) = match text.split_once('/') {
+            Some((b, s)) if b.starts_with('B') && s.starts_with('S') => (&b[1..], &s[1..]),
+            _ => return Err(ParseError::whole(text, "Expected rules like 'B3/S23'")),
+        };
+        // Each count along with the text it came from, for pointing at it in errors.
+        fn counts<'a>(text: &str, part: &'a str) -> Result<Vec<(usize, &'a str)>, ParseError> {
+            let tokens: Vec<&str> = if part.contains(',') {
+                part.split(',').collect()
+            } else {
+                part.char_indices().map(|(i, c)| &part[i..i + c.len_utf8()]).collect()
+            };
+            tokens.into_iter().map(|n| Ok((parse_at(text, n)?, n))).collect()
+        }
+        let birth = counts(text, b)?;
+        let survival = counts(text, s)?;
+        if let Some(&(_, zero)) = birth.iter().find(|&&(n, _)| n == 0) {
+            let msg = "B0 would make infinitely many cells active";
+            return Err(ParseError::at(text, zero, msg));
+        }
+        let rules = Rules {
+            birth: birth.into_iter().map(|(n, _)| n).collect(),
+            survival: survival.into_iter().map(|(n, _)| n).collect(),
+        };
+        Ok(rules)
+    }
+
+    fn next_state(&self, active: bool, neighbors: usize) -> bool {
+        if active {
+            self.survival.contains(&neighbors)
+        } else {
+            self.birth.contains(&neighbors)
+        }
+    }
+}
+
+/// The state of an automaton: its shape, rules and the set of active cells.
+pub struct Life<T: Topology> {
+    topology: T,
+    rules: Rules,
+    active: HashSet<T::Cell>,
+}
+
+impl<T: Topology> Life<T> {
+    pub fn new<I: IntoIterator<Item = T::Cell>>(topology: T, rules: Rules, active: I) -> Life<T> {
+        Life {
+            topology,
+            rules,
+            active: active.into_iter().collect(),
+        }
+    }
+
+    pub fn active(&self) -> &HashSet<T::Cell> {
+        &self.active
+    }
+
+    pub fn num_active(&self) -> usize {
+        self.active.len()
+    }
+
+    pub fn is_active(&self, cell: &T::Cell) -> bool {
+        self.active.contains(cell)
+    }
+
+    pub fn num_active_neighbors(&self, cell: &T::Cell) -> usize {
+        self.topology
+            .neighbors(cell)
+            .iter()
+            .filter(|n| self.active.contains(n))
+            .count()
+    }
+
+    /// Advance one generation.
+    pub fn step(&mut self) {
+        // Only cells next to an active cell can have any active neighbors.
+        let mut counts: HashMap<T::Cell, usize> = HashMap::new();
+        for cell in self.active.iter() {
+            for n in self.topology.neighbors(cell) {
+                *counts.entry(n).or_default() += 1;
+            }
+        }
+        let survivors = self
+            .active
+            .iter()
+            .filter(|cell| self.rules.next_state(true, *counts.get(cell).unwrap_or(&0)));
+        let births = counts
+            .iter()
+            .filter(|&(cell, &n)| !self.active.contains(cell) && self.rules.next_state(false, n))
+            .map(|(cell, _)| cell);
+        self.active = survivors.chain(births).copied().collect();
+    }
+
+    /// Advance `generations` generations.
+    pub fn run(&mut self, generations: usize) {
+        for _ in 0..generations {
+            self.step();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+
+    #[test]
+    fn test_square() {
+        assert_eq!(2, Square::<1>::new().neighbors(&[0]).len());
+        assert_eq!(8, Square::<2>::new().neighbors(&[0, 0]).len());
+        assert_eq!(80, Square::<4>::new().neighbors(&[0, 0, 0, 0]).len());
+        assert!(Square::<3>::new().neighbors(&[1, 1, 1]).contains(&[0, 2, 1]));
+    }
+
+    #[test]
+    fn test_rules() {
+        assert_eq!(Ok(Rules::conway()), Rules::parse("B3/S23"));
+        assert_eq!(
+            Ok(Rules { birth: vec![2], survival: vec![1, 12] }),
+            Rules::parse("B2/S1,12")
+        );
+        assert_eq!(Ok(Rules { birth: vec![], survival: vec![] }), Rules::parse("B/S"));
+        assert!(Rules::parse("B3S23").is_err());
+        assert_eq!(5, Rules::parse("B3/Sx").unwrap_err().column);
+        assert!(Rules::parse("B03/S23").is_err());
+        assert_eq!(5, Rules::parse("B2/Sé").unwrap_err().column);
+        assert_eq!(4, Rules::parse("B3,0/S").unwrap_err().column);
+        assert_eq!(2, Rules::parse("B\u{a0}0,1/S1").unwrap_err().column);
+    }
+
+    #[test]
+    fn test_blinker() {
+        let mut life = Life::new(Square::<2>::new(), Rules::conway(), vec![[0, -1], [0, 0], [0, 1]]);
+        assert_eq!(3, life.num_active_neighbors(&[1, 0]));
+        life.step();
+        let mut cells: Vec<_> = life.active().iter().copied().collect();
+        cells.sort_unstable();
+        assert_eq!(vec![[-1, 0], [0, 0], [1, 0]], cells);
+        life.step();
+        assert!(life.is_active(&[0, -1]));
+        assert_eq!(3, life.num_active());
+    }
+
+    #[test]
+    fn test_custom() {
+        // A ring of 5 cells: each cell is born with one neighbor, and none survive.
+        let ring = Custom::new(|&c: &i32| vec![(c + 1) % 5, (c + 4) % 5]);
+        let mut life = Life::new(ring, Rules::parse("B1/S").unwrap(), vec![0]);
+        life.step();
+        assert_eq!(2, life.num_active());
+        assert!(life.is_active(&1) && life.is_active(&4));
+    }
+}
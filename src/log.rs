@@ -0,0 +1,107 @@
+//! Leveled logging to stderr, off by default.
+//!
+//! `-v` turns on `info!`, for a summary or two per part, and `-vv` also turns on
+//! `debug!`, for traces of every step. The message isn't formatted unless its level
+//! is on, so trace points in hot loops cost one atomic load when logging is off.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Quiet = 0,
+    Info = 1,
+    Debug = 2,
+}
+
+static LEVEL: AtomicUsize = AtomicUsize::new(Level::Quiet as usize);
+
+pub fn set_level(level: Level) {
+    LEVEL.store(level as usize, Ordering::Relaxed);
+}
+
+pub fn level() -> Level {
+    match LEVEL.load(Ordering::Relaxed) {
+        0 => Level::Quiet,
+        1 => Level::Info,
+        _ => Level::Debug,
+    }
+}
+
+pub fn enabled(level: Level) -> bool {
+    LEVEL.load(Ordering::Relaxed) >= level as usize
+}
+
+/// Remove the verbosity flags (`-v`, `-vv`, `--verbose`) from `args`, wherever they
+/// are, and set the level from them. Repeated flags add up, so `-v -v` is `-vv`.
+pub fn take_flags(args: Vec<String>) -> Vec<String> {
+    let mut verbosity = 0;
+    let rest = args
+        .into_iter()
+        .filter(|arg| match arg.as_str() {
+            "--verbose" => {
+                verbosity += 1;
+                false
+            }
+            a if a.len() > 1 && a.starts_with('-') && a[1..].chars().all(|c| c == 'v') => {
+                verbosity += a.len() - 1;
+                false
+            }
+            _ => true,
+        })
+        .collect();
+    set_level(match verbosity {
+        0 => Level::Quiet,
+        1 => Level::Info,
+        _ => Level::Debug,
+    });
+    rest
+}
+
+/// Log a message at `-v` and above.
+#[macro_export]
+macro_rules! info {
+    ($($arg:tt)*) => {
+        if $crate::log::enabled($crate::log::Level::Info) {
+            eprintln!($($arg)*);
+        }
+    };
+}
+
+/// Log a message at `-vv`.
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)*) => {
+        if $crate::log::enabled($crate::log::Level::Debug) {
+            eprintln!($($arg)*);
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+
+    fn strings(args: &[&str]) -> Vec<String> {
+        args.iter().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn test_take_flags() {
+        // The level is global, so this is the only test that sets it.
+        let rest = take_flags(strings(&["run", "-vv", "9", "-", "5"]));
+        assert_eq!(strings(&["run", "9", "-", "5"]), rest);
+        assert_eq!(Level::Debug, level());
+        assert!(enabled(Level::Info));
+
+        take_flags(strings(&["run", "-v", "9"]));
+        assert_eq!(Level::Info, level());
+        assert!(!enabled(Level::Debug));
+
+        take_flags(strings(&["--verbose", "-v"]));
+        assert_eq!(Level::Debug, level());
+
+        assert_eq!(strings(&["-x", "-"]), take_flags(strings(&["-x", "-"])));
+        assert_eq!(Level::Quiet, level());
+    }
+}
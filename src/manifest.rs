@@ -0,0 +1,263 @@
+//! The expected answers manifest, `inputs/answers.toml`.
+//!
+//! This is a small subset of TOML: a list of `[[answer]]` tables, each with a
+//! `day`, an `input` file (relative to the manifest), optional `args` for the
+//! solution and the expected `part1` and/or `part2` answers.
+//!
+//! ```toml
+//! [[answer]]
+//! day = 9
+//! input = "day9.sample.txt"
+//! args = ["5"]
+//! part1 = 127
+//! part2 = 62
+//! ```
+
+use std::path::Path;
+
+use crate::error::ParseError;
+use crate::util::Source;
+
+/// The expected answers for one day on one input.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expected {
+    pub day: u32,
+    /// Path to the input, relative to the working directory.
+    pub input: String,
+    pub args: Vec<String>,
+    pub part1: Option<String>,
+    pub part2: Option<String>,
+}
+
+impl Expected {
+    /// The expected answer for part 1 or 2.
+    pub fn part(&self, part: u32) -> Option<&String> {
+        match part {
+            1 => self.part1.as_ref(),
+            2 => self.part2.as_ref(),
+            _ => None,
+        }
+    }
+}
+
+/// A TOML value: strings and integers are both kept as their text.
+enum Value {
+    Str(String),
+    Array(Vec<String>),
+}
+
+/// Parse a single string or integer starting at the beginning of `text`, returning it
+/// and the rest of the text.
+fn parse_scalar<'a>(line: &str, text: &'a str) -> Result<(String, &'a str), ParseError> {
+    if let Some(rest) = text.strip_prefix('"') {
+        let mut out = String::new();
+        let mut chars = rest.char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => return Ok((out, &rest[i + 1..])),
+                '\\' => match chars.next() {
+                    Some((_, '"')) => out.push('"'),
+                    Some((_, '\\')) => out.push('\\'),
+                    Some((_, 'n')) => out.push('\n'),
+                    _ => return Err(ParseError::at(line, &rest[i..i + 1], "Unknown escape sequence")),
+                },
+                _ => out.push(c),
+            }
+        }
+        return Err(ParseError::at(line, text, "Unterminated string"));
+    }
+
+    let end = text
+        .find(|c: char| c == ',' || c == ']' || c.is_whitespace())
+        .unwrap_or(text.len());
+    let num = &text[..end];
+    match num.replace('_', "").parse::<i64>() {
+        Ok(n) => Ok((n.to_string(), &text[end..])),
+        Err(_) => Err(ParseError::at(line, num, "Expected a string or integer")),
+    }
+}
+
+/// Strip a trailing comment, ignoring '#'s in strings.
+fn strip_comment(line: &str) -> &str {
+    let mut in_str = false;
+    let mut chars = line.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' if in_str => {
+                chars.next();
+            }
+            '"' => in_str = !in_str,
+            '#' if !in_str => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+fn parse_value(line: &str, text: &str) -> Result<Value, ParseError> {
+    let (value, rest) = match text.strip_prefix('[') {
+        Some(mut rest) => {
+            let mut items = vec![];
+            loop {
+                rest = rest.trim_start();
+                if let Some(r) = rest.strip_prefix(']') {
+                    rest = r;
+                    break;
+                }
+                let (item, r) = parse_scalar(line, rest)?;
+                items.push(item);
+                rest = r.trim_start();
+                if let Some(r) = rest.strip_prefix(',') {
+                    rest = r;
+                } else if !rest.starts_with(']') {
+                    return Err(ParseError::at(line, rest, "Expected ',' or ']'"));
+                }
+            }
+            (Value::Array(items), rest)
+        }
+        None => {
+            let (s, rest) = parse_scalar(line, text)?;
+            (Value::Str(s), rest)
+        }
+    };
+
+    if !rest.trim().is_empty() {
+        return Err(ParseError::at(line, rest.trim(), "Unexpected text after value"));
+    }
+    Ok(value)
+}
+
+/// Parse the manifest. Inputs are relative to `dir`.
+pub fn parse_manifest(text: &str, dir: &Path) -> Result<Vec<Expected>, ParseError> {
+    let mut answers: Vec<Expected> = vec![];
+    // The line each `[[answer]]` started on, for reporting missing keys.
+    let mut starts: Vec<(usize, &str)> = vec![];
+
+    for (i, raw_line) in text.lines().enumerate() {
+        let line = strip_comment(raw_line).trim();
+        if line.is_empty() {
+            continue;
+        }
+        let err = |e: ParseError| e.shift(i);
+        if line == "[[answer]]" {
+            answers.push(Expected {
+                day: 0,
+                input: String::new(),
+                args: vec![],
+                part1: None,
+                part2: None,
+            });
+            starts.push((i, raw_line));
+            continue;
+        }
+        if line.starts_with('[') {
+            return Err(err(ParseError::at(raw_line, line, "Expected '[[answer]]'")));
+        }
+
+        let eq = line
+            .find('=')
+            .ok_or_else(|| err(ParseError::at(raw_line, line, "Expected 'key = value'")))?;
+        let key = line[..eq].trim();
+        let value = parse_value(raw_line, line[eq + 1..].trim()).map_err(err)?;
+        let answer = answers
+            .last_mut()
+            .ok_or_else(|| err(ParseError::at(raw_line, key, "Expected '[[answer]]' before any keys")))?;
+
+        match (key, value) {
+            ("day", Value::Str(day)) => {
+                answer.day = day
+                    .parse()
+                    .map_err(|_| err(ParseError::at(raw_line, line, "Expected a day number")))?;
+            }
+            ("input", Value::Str(input)) => {
+                answer.input = dir.join(input).to_string_lossy().into_owned();
+            }
+            ("args", Value::Array(args)) => answer.args = args,
+            ("part1", Value::Str(v)) => answer.part1 = Some(v),
+            ("part2", Value::Str(v)) => answer.part2 = Some(v),
+            ("day", _) | ("input", _) | ("part1", _) | ("part2", _) | ("args", _) => {
+                return Err(err(ParseError::at(raw_line, line, &format!("Wrong type for {}", key))));
+            }
+            _ => {
+                return Err(err(ParseError::at(raw_line, key, &format!("Unknown key: {}", key))));
+            }
+        }
+    }
+
+    for (answer, &(i, line)) in answers.iter().zip(starts.iter()) {
+        if answer.day == 0 || answer.input.is_empty() {
+            return Err(ParseError::whole(line, "Each answer needs a day and an input").shift(i));
+        }
+    }
+    Ok(answers)
+}
+
+/// Read the manifest at `path`.
+pub fn read_manifest(path: &str) -> Result<Vec<Expected>, ParseError> {
+    let dir = Path::new(path).parent().unwrap_or_else(|| Path::new(""));
+    Source::read(path)?.parse(|text| parse_manifest(text, dir))
+}
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+
+    #[test]
+    fn test_parse_manifest() {
+        let answers = parse_manifest(
+            r#"
+# Day 9 takes the length of the preamble.
+[[answer]]
+day = 9
+input = "day9.sample.txt"  # comment
+args = ["5"]
+part1 = 127
+part2 = "62"
+
+[[answer]]
+day = 21
+input = "day21.sample.txt"
+part2 = "a,b # not a comment"
+"#,
+            Path::new("inputs"),
+        )
+        .unwrap();
+
+        assert_eq!(
+            answers,
+            vec![
+                Expected {
+                    day: 9,
+                    input: String::from("inputs/day9.sample.txt"),
+                    args: vec![String::from("5")],
+                    part1: Some(String::from("127")),
+                    part2: Some(String::from("62")),
+                },
+                Expected {
+                    day: 21,
+                    input: String::from("inputs/day21.sample.txt"),
+                    args: vec![],
+                    part1: None,
+                    part2: Some(String::from("a,b # not a comment")),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_manifest_errors() {
+        let dir = Path::new("");
+        let err = parse_manifest("[[answer]]\nday = 1\ninput = \"x\"\npart3 = 2", dir).unwrap_err();
+        assert_eq!((4, 1, "Unknown key: part3"), (err.line, err.column, err.message.as_str()));
+
+        let err = parse_manifest("[[answer]]\nday = 1\ninput = \"x", dir).unwrap_err();
+        assert_eq!("Unterminated string", err.message);
+
+        let err = parse_manifest("[[answer]]\nday = 1\n", dir).unwrap_err();
+        assert_eq!(1, err.line);
+
+        let err = parse_manifest("[[answer]]\nargs = [\"1\" \"2\"]", dir).unwrap_err();
+        assert_eq!((2, 13), (err.line, err.column));
+    }
+}
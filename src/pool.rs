@@ -0,0 +1,131 @@
+//! Just enough of a thread pool to run independent pieces of work in parallel.
+//!
+//! The number of threads comes from `--jobs` and defaults to 1, which runs
+//! everything on the calling thread, just as before. Work started from inside a pool
+//! thread also stays on that thread, so running the days in parallel doesn't multiply
+//! the threads that their searches use. The threads are scoped, so the work can
+//! borrow from the caller, and a panic in any of them is passed on to the caller.
+
+use std::cell::Cell;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+static JOBS: AtomicUsize = AtomicUsize::new(1);
+
+thread_local! {
+    static IN_POOL: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Set the number of threads to use. Anything below 1 means 1.
+pub fn set_jobs(jobs: usize) {
+    JOBS.store(jobs.max(1), Ordering::Relaxed);
+}
+
+pub fn jobs() -> usize {
+    JOBS.load(Ordering::Relaxed)
+}
+
+/// How many threads to use for `n` pieces of work.
+fn threads_for(n: usize) -> usize {
+    if IN_POOL.with(|p| p.get()) {
+        1
+    } else {
+        jobs().min(n)
+    }
+}
+
+/// Run `work` on each of `threads` new threads and wait for them all to finish.
+fn spawn<F: Fn() + Sync>(threads: usize, work: F) {
+    thread::scope(|scope| {
+        for _ in 0..threads {
+            scope.spawn(|| {
+                IN_POOL.with(|p| p.set(true));
+                work();
+            });
+        }
+    });
+}
+
+/// `f(i)` for each `i` in `0..n`, in order. The threads take the next index as they
+/// become free, so uneven work still gets spread out.
+pub fn map<R: Send, F: Fn(usize) -> R + Sync>(n: usize, f: F) -> Vec<R> {
+    let threads = threads_for(n);
+    if threads <= 1 {
+        return (0..n).map(f).collect();
+    }
+
+    let next = AtomicUsize::new(0);
+    let results = Mutex::new(Vec::with_capacity(n));
+    spawn(threads, || loop {
+        let i = next.fetch_add(1, Ordering::Relaxed);
+        if i >= n {
+            break;
+        }
+        let result = f(i);
+        results.lock().unwrap().push((i, result));
+    });
+
+    let mut results = results.into_inner().unwrap();
+    results.sort_unstable_by_key(|&(i, _)| i);
+    results.into_iter().map(|(_, r)| r).collect()
+}
+
+/// `f(i)` for the lowest `i` in `0..n` where it's `Some`, like `(0..n).find_map(f)`.
+/// The indices are handed out in order, and a thread stops once there's a match
+/// below the next index, so the answer is the same however many threads there are.
+pub fn find_first<R: Send, F: Fn(usize) -> Option<R> + Sync>(n: usize, f: F) -> Option<R> {
+    let threads = threads_for(n);
+    if threads <= 1 {
+        return (0..n).find_map(f);
+    }
+
+    let next = AtomicUsize::new(0);
+    let best = AtomicUsize::new(usize::MAX);
+    let found: Mutex<Option<(usize, R)>> = Mutex::new(None);
+    spawn(threads, || loop {
+        let i = next.fetch_add(1, Ordering::Relaxed);
+        if i >= n || i > best.load(Ordering::Relaxed) {
+            break;
+        }
+        if let Some(result) = f(i) {
+            best.fetch_min(i, Ordering::Relaxed);
+            let mut found = found.lock().unwrap();
+            if found.as_ref().is_none_or(|&(j, _)| i < j) {
+                *found = Some((i, result));
+            }
+        }
+    });
+    found.into_inner().unwrap().map(|(_, r)| r)
+}
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_pool() {
+        // The job count is global, but nothing else depends on it for its results.
+        set_jobs(4);
+        assert_eq!((0..100).map(|i| i * i).collect::<Vec<_>>(), map(100, |i| i * i));
+        assert!(map(0, |i| i).is_empty());
+
+        // The low indices take the longest, so they finish last.
+        let slow = |i: usize| {
+            thread::sleep(Duration::from_millis(20u64.saturating_sub(i as u64)));
+            if i % 7 == 3 {
+                Some(i)
+            } else {
+                None
+            }
+        };
+        assert_eq!(Some(3), find_first(50, slow));
+        assert_eq!(None, find_first(50, |_| None::<usize>));
+
+        // Nested work runs on the pool thread.
+        let threads = map(4, |_| map(4, |_| thread::current().id()));
+        assert!(threads.iter().all(|ids| ids.iter().all(|id| *id == ids[0])));
+    }
+}
@@ -0,0 +1,96 @@
+//! A small seeded random number generator, for property tests and generated inputs.
+//!
+//! It's SplitMix64: fast, tiny and good enough to shake out edge cases, but not for
+//! anything that needs real randomness. The same seed always gives the same values,
+//! so a failing case can be replayed from its seed.
+
+use std::fmt::Debug;
+use std::ops::Range;
+
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `0..n`. `n` must be positive.
+    pub fn below(&mut self, n: u64) -> u64 {
+        assert!(n > 0, "Can't pick a number below 0");
+        // Multiply-shift rather than %, which favors small numbers more.
+        ((self.next_u64() as u128 * n as u128) >> 64) as u64
+    }
+
+    /// A number in `range`, which must not be empty.
+    pub fn range(&mut self, range: Range<i64>) -> i64 {
+        assert!(range.start < range.end, "Can't pick from an empty range");
+        range.start + self.below((range.end - range.start) as u64) as i64
+    }
+
+    pub fn bool(&mut self) -> bool {
+        self.next_u64() >> 63 == 1
+    }
+
+    /// One of `items`, which must not be empty.
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len() as u64) as usize]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.below(i as u64 + 1) as usize;
+            items.swap(i, j);
+        }
+    }
+}
+
+/// Check that `prop` holds for `cases` values made by `gen`. Each case gets its own
+/// seed, which is in the panic message along with the value if the property fails.
+pub fn for_all<T: Debug>(cases: u64, mut gen: impl FnMut(&mut Rng) -> T, mut prop: impl FnMut(&T) -> bool) {
+    for seed in 0..cases {
+        let value = gen(&mut Rng::new(seed));
+        assert!(prop(&value), "Property failed with seed {}: {:?}", seed, value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+
+    #[test]
+    fn test_rng() {
+        let mut a = Rng::new(7);
+        let mut b = Rng::new(7);
+        assert_eq!((0..5).map(|_| a.next_u64()).collect::<Vec<_>>(), (0..5).map(|_| b.next_u64()).collect::<Vec<_>>());
+        assert_ne!(Rng::new(1).next_u64(), Rng::new(2).next_u64());
+
+        let mut rng = Rng::new(0);
+        let rolls: Vec<i64> = (0..1000).map(|_| rng.range(-3..3)).collect();
+        assert!(rolls.iter().all(|r| (-3..3).contains(r)));
+        assert!((-3..3).all(|n| rolls.contains(&n)));
+
+        let mut items: Vec<u32> = (0..20).collect();
+        rng.shuffle(&mut items);
+        assert_ne!((0..20).collect::<Vec<_>>(), items);
+        items.sort_unstable();
+        assert_eq!((0..20).collect::<Vec<_>>(), items);
+    }
+
+    #[test]
+    #[should_panic(expected = "Property failed with seed")]
+    fn test_for_all() {
+        for_all(100, |rng| rng.below(10), |&n| n < 9);
+    }
+}
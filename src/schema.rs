@@ -0,0 +1,183 @@
+//! Declarative rules for records of `key:value` fields, like day 4's passports.
+//!
+//! A `Schema` lists the fields a record may have, whether each is required, and
+//! the `Rule` its value must follow. Checking a record gives back every problem with
+//! it, rather than stopping at the first.
+
+use regex::Regex;
+use std::collections::HashMap;
+use std::fmt;
+
+/// What a field's value must look like.
+#[derive(Debug, Clone)]
+pub enum Rule {
+    /// A number from `min` to `max`, with exactly `digits` digits if that's given.
+    Number { digits: Option<usize>, min: u64, max: u64 },
+    /// A number followed by a unit, with a range for each unit, like `170cm`.
+    Measure(Vec<(String, u64, u64)>),
+    /// A match for a regex, which should be anchored at both ends.
+    Pattern(Regex),
+    /// Exactly one of a list of values.
+    OneOf(Vec<String>),
+    Any,
+}
+
+fn parse_number(s: &str) -> Option<u64> {
+    if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    s.parse().ok()
+}
+
+impl Rule {
+    /// Four digits from `min` to `max`.
+    pub fn year(min: u64, max: u64) -> Rule {
+        Rule::Number { digits: Some(4), min, max }
+    }
+
+    /// A number with one of the units, each given as `(unit, min, max)`.
+    pub fn measure(units: &[(&str, u64, u64)]) -> Rule {
+        Rule::Measure(units.iter().map(|&(unit, min, max)| (String::from(unit), min, max)).collect())
+    }
+
+    /// Panics if `re` isn't a valid regex, since schemas are written by hand.
+    pub fn pattern(re: &str) -> Rule {
+        Rule::Pattern(Regex::new(re).unwrap())
+    }
+
+    pub fn one_of(values: &[&str]) -> Rule {
+        Rule::OneOf(values.iter().map(|v| String::from(*v)).collect())
+    }
+
+    pub fn check(&self, value: &str) -> bool {
+        match self {
+            Rule::Number { digits, min, max } => {
+                digits.is_none_or(|d| value.len() == d)
+                    && parse_number(value).is_some_and(|n| (*min..=*max).contains(&n))
+            }
+            Rule::Measure(units) => units.iter().any(|(unit, min, max)| {
+                value
+                    .strip_suffix(unit.as_str())
+                    .and_then(parse_number)
+                    .is_some_and(|n| (*min..=*max).contains(&n))
+            }),
+            Rule::Pattern(re) => re.is_match(value),
+            Rule::OneOf(values) => values.iter().any(|v| v == value),
+            Rule::Any => true,
+        }
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Rule::Number { digits: Some(d), min, max } => write!(f, "{} digits from {} to {}", d, min, max),
+            Rule::Number { digits: None, min, max } => write!(f, "a number from {} to {}", min, max),
+            Rule::Measure(units) => {
+                let ranges: Vec<String> = units.iter().map(|(unit, min, max)| format!("{} to {}{}", min, max, unit)).collect();
+                write!(f, "{}", ranges.join(" or "))
+            }
+            Rule::Pattern(re) => write!(f, "a match for {}", re),
+            Rule::OneOf(values) => write!(f, "one of {}", values.join(", ")),
+            Rule::Any => write!(f, "anything"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Field {
+    pub key: String,
+    pub required: bool,
+    pub rule: Rule,
+}
+
+/// The fields a record may have. Fields which aren't in the schema are ignored.
+#[derive(Debug, Clone, Default)]
+pub struct Schema {
+    pub fields: Vec<Field>,
+}
+
+/// A problem with one field of a record.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldError<'a> {
+    Missing(&'a str),
+    Invalid { key: &'a str, value: &'a str, rule: String },
+}
+
+impl fmt::Display for FieldError<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FieldError::Missing(key) => write!(f, "missing {}", key),
+            FieldError::Invalid { key, value, rule } => write!(f, "invalid {}:{} (expected {})", key, value, rule),
+        }
+    }
+}
+
+impl Schema {
+    pub fn new() -> Schema {
+        Schema::default()
+    }
+
+    pub fn required(mut self, key: &str, rule: Rule) -> Schema {
+        self.fields.push(Field { key: String::from(key), required: true, rule });
+        self
+    }
+
+    pub fn optional(mut self, key: &str, rule: Rule) -> Schema {
+        self.fields.push(Field { key: String::from(key), required: false, rule });
+        self
+    }
+
+    /// Every problem with `record`, in the order of the schema's fields.
+    pub fn check<'a>(&'a self, record: &HashMap<&'a str, &'a str>) -> Vec<FieldError<'a>> {
+        let mut errors = vec![];
+        for field in self.fields.iter() {
+            match record.get(field.key.as_str()) {
+                None if field.required => errors.push(FieldError::Missing(&field.key)),
+                None => {}
+                Some(value) if !field.rule.check(value) => errors.push(FieldError::Invalid {
+                    key: &field.key,
+                    value,
+                    rule: field.rule.to_string(),
+                }),
+                Some(_) => {}
+            }
+        }
+        errors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+
+    #[test]
+    fn test_rules() {
+        let year = Rule::year(1920, 2002);
+        assert!(year.check("2002") && !year.check("2003") && !year.check("19200") && !year.check("+200"));
+        let hgt = Rule::measure(&[("cm", 150, 193), ("in", 59, 76)]);
+        assert!(hgt.check("60in") && hgt.check("190cm") && !hgt.check("190in") && !hgt.check("190") && !hgt.check("cm"));
+        assert!(Rule::pattern(r"^#[0-9a-f]{6}$").check("#123abc"));
+        assert!(!Rule::one_of(&["amb", "blu"]).check("wat"));
+
+        assert_eq!("4 digits from 1920 to 2002", year.to_string());
+        assert_eq!("150 to 193cm or 59 to 76in", hgt.to_string());
+        assert_eq!("one of amb, blu", Rule::one_of(&["amb", "blu"]).to_string());
+    }
+
+    #[test]
+    fn test_check() {
+        let schema = Schema::new()
+            .required("byr", Rule::year(1920, 2002))
+            .required("hcl", Rule::pattern(r"^#[0-9a-f]{6}$"))
+            .required("pid", Rule::Any)
+            .optional("cid", Rule::Number { digits: None, min: 1, max: 999 });
+        let record: HashMap<&str, &str> = [("byr", "2003"), ("hcl", "#123abc"), ("cid", "0"), ("xyz", "1")].iter().copied().collect();
+        let errors: Vec<String> = schema.check(&record).iter().map(|e| e.to_string()).collect();
+        assert_eq!(
+            vec!["invalid byr:2003 (expected 4 digits from 1920 to 2002)", "missing pid", "invalid cid:0 (expected a number from 1 to 999)"],
+            errors
+        );
+    }
+}
@@ -0,0 +1,336 @@
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use crate::alloc::{self, AllocStats};
+use crate::bench::{self, BenchOptions, DayBench};
+use crate::error::ParseError;
+use crate::generate::Generated;
+use crate::log;
+use crate::rng::Rng;
+use crate::util::{json_string, Source};
+
+/// A solution to one day's puzzle.
+///
+/// Parsing is split out from solving so that both parts can share the parsed input
+/// and so that the runner can time each phase separately.
+pub trait Solution {
+    /// The parsed puzzle input.
+    type Input;
+
+    /// Which day of the Advent calendar this solves.
+    const DAY: u32;
+
+    /// Parse the puzzle input. `args` are any extra command-line arguments, e.g. the
+    /// length of the preamble for day 9.
+    fn parse(source: &Source, args: &[String]) -> Result<Self::Input, ParseError>;
+
+    /// The answer to part 1, or `None` if it isn't implemented.
+    fn part1(_input: &Self::Input) -> Option<String> {
+        None
+    }
+
+    /// The answer to part 2, or `None` if it isn't implemented.
+    fn part2(_input: &Self::Input) -> Option<String> {
+        None
+    }
+
+    /// Anything to show besides the answers, as lines of text, after writing any files
+    /// that the extra args asked for. The runner calls this once after parsing,
+    /// whichever parts run, and it isn't timed. `bench` never calls it.
+    fn extras(_input: &Self::Input) -> Result<Vec<String>, String> {
+        Ok(vec![])
+    }
+
+    /// A random input of roughly `size` records, or `None` if there's no generator
+    /// for this day. See the `generate` module.
+    fn generate(_rng: &mut Rng, _size: usize) -> Option<Generated> {
+        None
+    }
+}
+
+/// Parse the `i`th extra argument to a solution, or use `default` if it wasn't given.
+pub fn parse_arg<T: FromStr>(args: &[String], i: usize, default: T) -> Result<T, ParseError> {
+    match args.get(i) {
+        Some(arg) => arg
+            .parse::<T>()
+            .map_err(|_| ParseError::arg(arg, &format!("Invalid argument: '{}'", arg))),
+        None => Ok(default),
+    }
+}
+
+/// The values of every `--name VALUE` in a solution's extra args, in order.
+pub fn flag_values<'a>(args: &'a [String], name: &str) -> Result<Vec<&'a str>, ParseError> {
+    let mut values = vec![];
+    let mut i = 0;
+    while i < args.len() {
+        if args[i] == name {
+            match args.get(i + 1) {
+                Some(value) => values.push(value.as_str()),
+                None => return Err(ParseError::arg(name, &format!("{} needs a value", name))),
+            }
+            i += 2;
+        } else {
+            i += 1;
+        }
+    }
+    Ok(values)
+}
+
+/// Parse the value of the last `--name VALUE` in a solution's extra args, or use
+/// `default` if there isn't one.
+pub fn parse_flag<T: FromStr>(args: &[String], name: &str, default: T) -> Result<T, ParseError> {
+    match flag_values(args, name)?.last() {
+        Some(value) => value
+            .parse::<T>()
+            .map_err(|_| ParseError::arg(value, &format!("Invalid value for {}: '{}'", name, value))),
+        None => Ok(default),
+    }
+}
+
+/// Check that a solution's extra args are all `--flag VALUE` pairs for the given flags.
+pub fn check_flags(args: &[String], flags: &[&str]) -> Result<(), ParseError> {
+    for pair in args.chunks(2) {
+        if !flags.contains(&pair[0].as_str()) {
+            let msg = format!("Unknown argument: '{}', expected {}", pair[0], flags.join(" or "));
+            return Err(ParseError::arg(&pair[0], &msg));
+        }
+    }
+    Ok(())
+}
+
+/// The answer to one part of a puzzle, along with how long it took to compute and
+/// what it allocated.
+#[derive(Debug, Clone)]
+pub struct PartResult {
+    pub part: u32,
+    pub answer: Option<String>,
+    pub elapsed: Duration,
+    pub alloc: AllocStats,
+}
+
+impl PartResult {
+    /// This result as a single line of JSON, e.g.
+    /// `{"day": 13, "part": 1, "answer": "295", "elapsed_ms": 0.012, "allocations": 3,
+    /// "peak_bytes": 96}`. The answer is `null` if the part isn't implemented.
+    pub fn to_json(&self, day: u32) -> String {
+        format!(
+            "{{\"day\": {}, \"part\": {}, \"answer\": {}, \"elapsed_ms\": {:.6}, \"allocations\": {}, \"peak_bytes\": {}}}",
+            day,
+            self.part,
+            self.answer.as_deref().map_or(String::from("null"), json_string),
+            self.elapsed.as_secs_f64() * 1000.0,
+            self.alloc.allocations,
+            self.alloc.peak
+        )
+    }
+}
+
+/// The result of running one day's solution on an input.
+#[derive(Debug, Clone)]
+pub struct Run {
+    pub parse_time: Duration,
+    pub parse_alloc: AllocStats,
+    pub parts: Vec<PartResult>,
+    /// What `Solution::extras` showed, or why it failed.
+    pub extras: Result<Vec<String>, String>,
+}
+
+type RunFn = fn(&Source, &[String], &[u32]) -> Result<Run, ParseError>;
+type BenchFn = fn(&Source, &[String], &BenchOptions) -> Result<DayBench, ParseError>;
+type GenerateFn = fn(&mut Rng, usize) -> Option<Generated>;
+
+/// A `Solution` with its types erased, so that all the days can go in one list. It's
+/// only fn pointers, so the days can be shared between threads and run on any of them.
+pub struct Day {
+    pub day: u32,
+    run: RunFn,
+    bench: BenchFn,
+    generate: GenerateFn,
+}
+
+impl Day {
+    pub fn of<S: Solution>() -> Day {
+        Day {
+            day: S::DAY,
+            run: run::<S>,
+            bench: bench::bench::<S>,
+            generate: S::generate,
+        }
+    }
+
+    /// The real puzzle input for this day.
+    pub fn default_input(&self) -> String {
+        format!("inputs/day{}.txt", self.day)
+    }
+
+    /// Parse the input and solve each of the requested parts (1 and/or 2).
+    pub fn run(&self, source: &Source, args: &[String], parts: &[u32]) -> Result<Run, ParseError> {
+        (self.run)(source, args, parts)
+    }
+
+    /// Time parsing and each implemented part separately, over several iterations.
+    pub fn bench(&self, source: &Source, args: &[String], opts: &BenchOptions) -> Result<DayBench, ParseError> {
+        (self.bench)(source, args, opts)
+    }
+
+    /// A random input of roughly `size` records from `seed`, if this day has a generator.
+    pub fn generate(&self, seed: u64, size: usize) -> Option<Generated> {
+        (self.generate)(&mut Rng::new(seed), size)
+    }
+}
+
+fn run<S: Solution>(source: &Source, args: &[String], parts: &[u32]) -> Result<Run, ParseError> {
+    let now = Instant::now();
+    let (input, parse_alloc) = alloc::measure(|| S::parse(source, args));
+    let input = input?;
+    let parse_time = now.elapsed();
+    let extras = S::extras(&input);
+
+    let parts = parts
+        .iter()
+        .map(|&part| {
+            let now = Instant::now();
+            let (answer, alloc) = alloc::measure(|| match part {
+                1 => S::part1(&input),
+                2 => S::part2(&input),
+                _ => None,
+            });
+            PartResult {
+                part,
+                answer,
+                elapsed: now.elapsed(),
+                alloc,
+            }
+        })
+        .collect();
+
+    Ok(Run {
+        parse_time,
+        parse_alloc,
+        parts,
+        extras,
+    })
+}
+
+/// Every day that has a solution, in order.
+pub fn days() -> Vec<Day> {
+    use crate::*;
+    vec![
+        Day::of::<day1::Solver>(),
+        Day::of::<day2::Solver>(),
+        Day::of::<day3::Solver>(),
+        Day::of::<day4::Solver>(),
+        Day::of::<day5::Solver>(),
+        Day::of::<day6::Solver>(),
+        Day::of::<day7::Solver>(),
+        Day::of::<day8::Solver>(),
+        Day::of::<day9::Solver>(),
+        Day::of::<day10::Solver>(),
+        Day::of::<day11::Solver>(),
+        Day::of::<day12::Solver>(),
+        Day::of::<day13::Solver>(),
+        Day::of::<day14::Solver>(),
+        Day::of::<day15::Solver>(),
+        Day::of::<day16::Solver>(),
+        Day::of::<day17::Solver>(),
+        Day::of::<day18::Solver>(),
+        Day::of::<day19::Solver>(),
+        Day::of::<day20::Solver>(),
+        Day::of::<day21::Solver>(),
+        Day::of::<day22::Solver>(),
+        Day::of::<day23::Solver>(),
+        Day::of::<day24::Solver>(),
+        Day::of::<day25::Solver>(),
+    ]
+}
+
+/// Look up the solution for a single day.
+pub fn find_day(day: u32) -> Option<Day> {
+    days().into_iter().find(|d| d.day == day)
+}
+
+pub fn format_duration(d: Duration) -> String {
+    format!("{:.3}ms", d.as_secs_f64() * 1000.0)
+}
+
+/// `main` for the single-day binaries: `dayN [-v|-vv] <input> [args...]`. The input may
+/// be `-` for stdin.
+pub fn main<S: Solution>() {
+    let args = log::take_flags(std::env::args().collect());
+    if args.len() < 2 {
+        panic!("Expected an input file, got {}: {:?}", args.len(), args);
+    }
+
+    let run = Source::read(&args[1]).and_then(|source| Day::of::<S>().run(&source, &args[2..], &[1, 2]));
+    let run = match run {
+        Ok(run) => run,
+        Err(e) => {
+            eprintln!("{}", e.diagnostic());
+            std::process::exit(1);
+        }
+    };
+    for part in run.parts {
+        if let Some(answer) = part.answer {
+            println!("Part {}: {}", part.part, answer);
+        }
+    }
+    match run.extras {
+        Ok(lines) => lines.iter().for_each(|line| println!("{}", line)),
+        Err(e) => {
+            eprintln!("error: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+
+    #[test]
+    fn test_to_json() {
+        let part = PartResult {
+            part: 2,
+            answer: Some(String::from("mxmxvkd,sqjhc")),
+            elapsed: Duration::from_micros(1500),
+            alloc: AllocStats {
+                allocations: 3,
+                allocated: 120,
+                peak: 96,
+            },
+        };
+        assert_eq!(
+            "{\"day\": 21, \"part\": 2, \"answer\": \"mxmxvkd,sqjhc\", \"elapsed_ms\": 1.500000, \
+             \"allocations\": 3, \"peak_bytes\": 96}",
+            part.to_json(21)
+        );
+        let part = PartResult { answer: None, ..part };
+        assert!(part.to_json(21).contains("\"answer\": null"));
+    }
+
+    #[test]
+    fn test_flags() {
+        let args: Vec<String> = ["--k", "4", "--target", "100", "--k", "5"].iter().map(|s| s.to_string()).collect();
+        assert_eq!(vec!["4", "5"], flag_values(&args, "--k").unwrap());
+        assert_eq!(Some(5), parse_flag(&args, "--k", 2).ok());
+        assert_eq!(Some(7), parse_flag(&args, "--slope", 7).ok());
+        assert!(check_flags(&args, &["--k", "--target"]).is_ok());
+        assert!(check_flags(&args, &["--k"]).is_err());
+
+        let args = vec![String::from("--k")];
+        assert!(parse_flag(&args, "--k", 2).is_err());
+        let args = vec![String::from("--k"), String::from("x")];
+        assert_eq!(ParseError::arg("x", "Invalid value for --k: 'x'"), parse_flag(&args, "--k", 2).unwrap_err());
+    }
+
+    #[test]
+    fn test_send_sync() {
+        // `aoc run all --jobs N` shares the days and sends their results between threads.
+        fn send_sync<T: Send + Sync>() {}
+        send_sync::<Day>();
+        send_sync::<Source>();
+        send_sync::<Run>();
+        send_sync::<ParseError>();
+    }
+}
@@ -1,7 +1,11 @@
+use std::collections::HashMap;
 use std::fs::File;
 use std::io::{self, BufRead};
 use std::path::Path;
 
+use crate::error::ParseError;
+use crate::grid::Grid;
+
 // Helper from https://doc.rust-lang.org/stable/rust-by-example/std_misc/file/read_lines.html
 pub fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
 where P: AsRef<Path>, {
@@ -9,6 +13,311 @@ where P: AsRef<Path>, {
     Ok(io::BufReader::new(file).lines())
 }
 
+/// Read a whole file into a string.
+pub fn read_file(path: &str) -> Result<String, ParseError> {
+    std::fs::read_to_string(path)
+        .map_err(|e| ParseError::general(&format!("Unable to read input: {}", e)).in_file(path))
+}
+
+pub fn write_file(path: &str, text: &str) -> Result<(), String> {
+    std::fs::write(path, text).map_err(|e| format!("Unable to write {}: {}", path, e))
+}
+
+/// Clean up the text of a puzzle input: drop a UTF-8 byte order mark, turn CRLF line
+/// endings into LF, blank out lines which are only whitespace and remove any
+/// trailing blank lines (and the final newline).
+pub fn normalize(text: &str) -> String {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let lines: Vec<&str> = text
+        .split('\n')
+        .map(|line| line.strip_suffix('\r').unwrap_or(line))
+        .map(|line| if line.trim().is_empty() { "" } else { line })
+        .collect();
+    let end = lines.iter().rposition(|line| !line.is_empty()).map_or(0, |i| i + 1);
+    lines[..end].join("\n")
+}
+
+/// Parse each line of `text` with `f`, numbering any error by its line in `text`.
+pub fn parse_text_lines<T, F>(text: &str, mut f: F) -> Result<Vec<T>, ParseError>
+where F: FnMut(&str) -> Result<T, ParseError>, {
+    text.lines()
+        .enumerate()
+        .map(|(i, line)| f(line).map_err(|e| e.shift(i)))
+        .collect()
+}
+
+/// Parse each line of the file at `path` with `f`, tagging errors with the file and line.
+pub fn parse_lines<T, F>(path: &str, f: F) -> Result<Vec<T>, ParseError>
+where F: FnMut(&str) -> Result<T, ParseError>, {
+    Source::read(path)?.parse_lines(f)
+}
+
+/// Split `text` into paragraphs separated by blank lines, along with the (0-based)
+/// line each starts on. Runs of several blank lines count as one separator.
+pub fn paragraphs(text: &str) -> Vec<(usize, &str)> {
+    let mut out = vec![];
+    let mut line = 0;
+    for para in text.split("\n\n") {
+        let skipped = para.len() - para.trim_start_matches('\n').len();
+        if skipped < para.len() {
+            out.push((line + skipped, &para[skipped..]));
+        }
+        line += para.matches('\n').count() + 2;
+    }
+    out
+}
+
+/// Parse each paragraph of `text` with `f`, which reports errors relative to the paragraph.
+pub fn parse_paragraphs<T, F>(text: &str, mut f: F) -> Result<Vec<T>, ParseError>
+where F: FnMut(&str) -> Result<T, ParseError>, {
+    paragraphs(text)
+        .into_iter()
+        .map(|(start, para)| f(para).map_err(|e| e.shift(start)))
+        .collect()
+}
+
+/// How the fields of a record are written. The default is day 4's: `key:value`
+/// pairs separated by spaces or line breaks.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RecordFormat {
+    /// What goes between a key and its value, or None if the fields are bare values,
+    /// which get an empty key.
+    pub pair: Option<char>,
+    /// What goes between fields on a line, or None for any whitespace. Line breaks
+    /// always separate fields.
+    pub delimiter: Option<char>,
+}
+
+impl Default for RecordFormat {
+    fn default() -> RecordFormat {
+        RecordFormat { pair: Some(':'), delimiter: None }
+    }
+}
+
+/// One field of a record, and where it is.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Field<'a> {
+    pub key: &'a str,
+    pub value: &'a str,
+    /// The 1-based line and column the field starts at.
+    pub line: usize,
+    pub column: usize,
+}
+
+/// A paragraph of fields, which may span several lines.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record<'a> {
+    /// The 1-based line the record starts on.
+    pub line: usize,
+    pub text: &'a str,
+    pub fields: Vec<Field<'a>>,
+}
+
+/// The 1-based line a record which starts on `line` ends on.
+fn last_line(line: usize, text: &str) -> usize {
+    line + text.lines().count() - 1
+}
+
+/// The last value for `key`.
+fn field_value<'a, I: DoubleEndedIterator<Item = (&'a str, &'a str)>>(mut fields: I, key: &str) -> Option<&'a str> {
+    fields.rfind(|&(k, _)| k == key).map(|(_, v)| v)
+}
+
+impl<'a> Record<'a> {
+    pub fn last_line(&self) -> usize {
+        last_line(self.line, self.text)
+    }
+
+    /// The fields by key. If a key is repeated, the last value wins.
+    pub fn map(&self) -> HashMap<&'a str, &'a str> {
+        self.fields.iter().map(|f| (f.key, f.value)).collect()
+    }
+
+    /// The last value for `key`.
+    pub fn get(&self, key: &str) -> Option<&'a str> {
+        field_value(self.fields.iter().map(|f| (f.key, f.value)), key)
+    }
+
+    /// An error pointing at `part` of `field`'s value.
+    pub fn error(&self, field: &Field<'a>, part: &str, message: &str) -> ParseError {
+        let line = self.text.lines().nth(field.line - self.line).unwrap_or_default();
+        ParseError::at(line, part, message).shift(field.line - 1)
+    }
+}
+
+/// A `Record` which owns its text, so that it can outlive the input.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OwnedRecord {
+    /// The 1-based line the record starts on.
+    pub line: usize,
+    pub text: String,
+    pub fields: Vec<(String, String)>,
+}
+
+impl OwnedRecord {
+    pub fn last_line(&self) -> usize {
+        last_line(self.line, &self.text)
+    }
+
+    /// The fields by key. If a key is repeated, the last value wins.
+    pub fn map(&self) -> HashMap<&str, &str> {
+        self.fields.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect()
+    }
+
+    /// The last value for `key`.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        field_value(self.fields.iter().map(|(k, v)| (k.as_str(), v.as_str())), key)
+    }
+}
+
+impl From<&Record<'_>> for OwnedRecord {
+    fn from(record: &Record) -> OwnedRecord {
+        OwnedRecord {
+            line: record.line,
+            text: String::from(record.text),
+            fields: record.fields.iter().map(|f| (String::from(f.key), String::from(f.value))).collect(),
+        }
+    }
+}
+
+fn read_record(start: usize, para: &str, format: RecordFormat) -> Result<Record<'_>, ParseError> {
+    let mut fields = vec![];
+    for (i, line) in para.lines().enumerate() {
+        let tokens: Vec<&str> = match format.delimiter {
+            None => line.split_whitespace().collect(),
+            Some(d) => line.split(d).map(str::trim).filter(|t| !t.is_empty()).collect(),
+        };
+        for token in tokens {
+            let (key, value) = match format.pair {
+                None => ("", token),
+                Some(sep) => match token.split_once(sep).map(|(key, value)| (key.trim(), value.trim())) {
+                    Some((key, value)) if !key.is_empty() => (key, value),
+                    _ => {
+                        let msg = format!("Expected a field like 'key{}value'", sep);
+                        return Err(ParseError::at(line, token, &msg).shift(start + i));
+                    }
+                },
+            };
+            let column = line[..token.as_ptr() as usize - line.as_ptr() as usize].chars().count() + 1;
+            fields.push(Field { key, value, line: start + i + 1, column });
+        }
+    }
+    Ok(Record { line: start + 1, text: para, fields })
+}
+
+/// Read each paragraph of `text` as a record.
+pub fn records(text: &str, format: RecordFormat) -> Result<Vec<Record<'_>>, ParseError> {
+    paragraphs(text).into_iter().map(|(start, para)| read_record(start, para, format)).collect()
+}
+
+/// Read each paragraph of `text` as a record and turn it into a `T` with `f`. Errors
+/// from `f` should come from `Record::error`, which knows where the record is.
+pub fn parse_records<T, F>(text: &str, format: RecordFormat, f: F) -> Result<Vec<T>, ParseError>
+where F: FnMut(&Record) -> Result<T, ParseError>, {
+    records(text, format)?.iter().map(f).collect()
+}
+
+/// A puzzle input, normalized (see `normalize`) so that line endings, a byte order
+/// mark or trailing blank lines don't trip up the parsers.
+pub struct Source {
+    /// Where the input came from, for error messages.
+    pub name: String,
+    text: String,
+}
+
+impl Source {
+    /// Read the file at `path`, or standard input if it's `-`.
+    pub fn read(path: &str) -> Result<Source, ParseError> {
+        if path == "-" {
+            let stdin = io::stdin();
+            let lock = stdin.lock();
+            return Source::from_reader("<stdin>", lock);
+        }
+        Ok(Source::new(path, &read_file(path)?))
+    }
+
+    /// Read everything from `reader`.
+    pub fn from_reader<R: BufRead>(name: &str, mut reader: R) -> Result<Source, ParseError> {
+        let mut text = String::new();
+        reader
+            .read_to_string(&mut text)
+            .map_err(|e| ParseError::general(&format!("Unable to read input: {}", e)).in_file(name))?;
+        Ok(Source::new(name, &text))
+    }
+
+    pub fn new(name: &str, text: &str) -> Source {
+        Source {
+            name: String::from(name),
+            text: normalize(text),
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn lines(&self) -> std::str::Lines<'_> {
+        self.text.lines()
+    }
+
+    /// Blank-line separated paragraphs, with the (0-based) line each starts on.
+    pub fn paragraphs(&self) -> Vec<(usize, &str)> {
+        paragraphs(&self.text)
+    }
+
+    /// Each paragraph as a record.
+    pub fn records(&self, format: RecordFormat) -> Result<Vec<Record<'_>>, ParseError> {
+        records(&self.text, format).map_err(|e| e.in_file(&self.name))
+    }
+
+    /// Each paragraph as a record, turned into a `T` with `f`.
+    pub fn parse_records<T, F>(&self, format: RecordFormat, f: F) -> Result<Vec<T>, ParseError>
+    where F: FnMut(&Record) -> Result<T, ParseError>, {
+        self.parse(|text| parse_records(text, format, f))
+    }
+
+    /// A grid with one character per cell, mapped with `f`.
+    pub fn grid<T, F: FnMut(char) -> Option<T>>(&self, f: F) -> Result<Grid<T>, ParseError> {
+        self.parse(|text| Grid::parse(text, f))
+    }
+
+    /// Parse the whole text with `f`, tagging any error with the source's name.
+    pub fn parse<T, F>(&self, f: F) -> Result<T, ParseError>
+    where F: FnOnce(&str) -> Result<T, ParseError>, {
+        f(&self.text).map_err(|e| e.in_file(&self.name))
+    }
+
+    /// Parse each line with `f`.
+    pub fn parse_lines<T, F>(&self, f: F) -> Result<Vec<T>, ParseError>
+    where F: FnMut(&str) -> Result<T, ParseError>, {
+        self.parse(|text| parse_text_lines(text, f))
+    }
+
+    /// Parse each paragraph with `f`, which reports errors relative to the paragraph.
+    pub fn parse_paragraphs<T, F>(&self, f: F) -> Result<Vec<T>, ParseError>
+    where F: FnMut(&str) -> Result<T, ParseError>, {
+        self.parse(|text| parse_paragraphs(text, f))
+    }
+}
+
+/// Quote a string for JSON output.
+pub fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
 /*
 pub fn read_chunks(path: &str) -> io::Result<std::str::Split<'_, &str>> {
     Ok(std::fs::read_to_string(path)?.split("\n\n"))
@@ -40,3 +349,67 @@ macro_rules! map(
         }
      };
 );
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!("a\nb\n\nc", normalize("\u{feff}a\r\nb\r\n  \r\nc\r\n\r\n\n"));
+        assert_eq!("a\nb", normalize("a\nb\n"));
+        assert_eq!("", normalize("\n \n"));
+        assert_eq!("\nx", normalize("\nx"));
+    }
+
+    #[test]
+    fn test_paragraphs() {
+        assert_eq!(vec![(0, "a\nb"), (3, "c"), (7, "d")], paragraphs("a\nb\n\nc\n\n\n\nd"));
+        assert_eq!(Vec::<(usize, &str)>::new(), paragraphs(""));
+
+        let source = Source::from_reader("test.txt", "1\r\n2\r\n\r\nx\r\n".as_bytes()).unwrap();
+        let err = source
+            .parse_paragraphs(|p| parse_text_lines(p, |l| crate::error::parse_at::<i32>(l, l)))
+            .unwrap_err();
+        assert_eq!(("test.txt", 4), (err.file.as_str(), err.line));
+    }
+
+    #[test]
+    fn test_records() {
+        let text = "a:1 b:2\n  c:x:y\n\n\nb:3 b:4";
+        let recs = records(text, RecordFormat::default()).unwrap();
+        assert_eq!(2, recs.len());
+        assert_eq!((1, 2), (recs[0].line, recs[0].last_line()));
+        assert_eq!(Field { key: "c", value: "x:y", line: 2, column: 3 }, recs[0].fields[2]);
+        assert_eq!(vec![("a", "1"), ("b", "2"), ("c", "x:y")], {
+            let mut fields: Vec<_> = recs[0].map().into_iter().collect();
+            fields.sort();
+            fields
+        });
+        assert_eq!((5, Some("4")), (recs[1].line, recs[1].get("b")));
+
+        let err = recs[0].error(&recs[0].fields[2], &recs[0].fields[2].value[2..], "Bad y");
+        assert_eq!((2, 7, 1), (err.line, err.column, err.len));
+        let err = parse_records(text, RecordFormat::default(), |r| match r.get("c") {
+            Some(_) => Ok(()),
+            None => Err(r.error(&r.fields[0], r.fields[0].value, "No c")),
+        });
+        assert_eq!((5, 3), err.map_err(|e| (e.line, e.column)).unwrap_err());
+
+        let err = records("a:1\nb c:2", RecordFormat::default()).unwrap_err();
+        assert_eq!((2, 1, "Expected a field like 'key:value'"), (err.line, err.column, err.message.as_str()));
+
+        // Bare values, and other separators.
+        let people = records("abc\nx", RecordFormat { pair: None, delimiter: None }).unwrap();
+        assert_eq!(vec!["abc", "x"], people[0].fields.iter().map(|f| f.value).collect::<Vec<_>>());
+        let ini = records("a = 1; b = 2", RecordFormat { pair: Some('='), delimiter: Some(';') }).unwrap();
+        assert_eq!(Some("2"), ini[0].get("b"));
+        assert_eq!(Some("1"), OwnedRecord::from(&ini[0]).get("a"));
+    }
+
+    #[test]
+    fn test_json_string() {
+        assert_eq!(r#""a\"b\\c\n\u0001""#, json_string("a\"b\\c\n\u{1}"));
+    }
+}
@@ -0,0 +1,19 @@
+// On its own, since the allocation counts are for the whole process and other tests
+// would run alongside it.
+use aoc2020::alloc::CountingAlloc;
+use aoc2020::solution::find_day;
+use aoc2020::util::Source;
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+#[test]
+fn run_counts_allocations() {
+    // Day 15 keeps a slot for every round, so part 1 needs at least 2020 i32s.
+    let run = find_day(15).unwrap().run(&Source::new("<sample>", "0,3,6"), &[], &[1]).unwrap();
+    let part = &run.parts[0];
+    assert_eq!(Some("436"), part.answer.as_deref());
+    assert!(part.alloc.allocations >= 1);
+    assert!(part.alloc.peak >= 2020 * 4, "{:?}", part.alloc);
+    assert!(part.alloc.allocated >= part.alloc.peak as u64);
+}
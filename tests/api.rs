@@ -0,0 +1,130 @@
+// These tests use the solvers the way another crate would: only through the public API.
+use aoc2020::error::ParseError;
+use aoc2020::manifest::read_manifest;
+use aoc2020::solution::{find_day, Solution};
+use aoc2020::util::Source;
+use aoc2020::{day1, day13, day18, day20, day23, day7};
+
+#[test]
+fn day7_sample() {
+    let graph = day7::read_rules("inputs/day7.sample.txt").unwrap();
+    let gold = graph.id("shiny gold").unwrap();
+    assert_eq!(4, graph.ancestors(gold).len());
+    assert_eq!(32, graph.bags_inside(gold));
+}
+
+#[test]
+fn day13_sample() {
+    let schedule = day13::read_schedule("inputs/day13.sample.txt").unwrap();
+    assert_eq!(295, day13::earliest_bus(&schedule));
+    assert_eq!(1068781, day13::earliest_timestamp(&schedule));
+    assert_eq!(651, day13::first_congruence(59, 2, 13, 1));
+}
+
+#[test]
+fn day18_evaluate() {
+    assert_eq!(Ok(46), day18::evaluate("2 * 3 + (4 * 5)"));
+}
+
+#[test]
+fn day20_sample() {
+    let tiles = day20::read_tiles("inputs/day20.sample.txt").unwrap();
+    assert_eq!(20899048083289, day20::corner_product(&tiles));
+    assert_eq!(Some(273), day20::count_non_dragons(&tiles));
+
+    let tile = &tiles[0];
+    for &op in day20::OPS.iter() {
+        let transformed = day20::transform_tile(tile, op);
+        assert_eq!(day20::transform_px(&tile.px, op), transformed.px);
+    }
+}
+
+#[test]
+fn day23_cups() {
+    let mut cups = day23::Cups::new(&[3, 8, 9, 1, 2, 5, 4, 6, 7], 9);
+    cups.play_one_round();
+    assert_eq!(5, cups.next(1));
+}
+
+#[test]
+fn solver_via_trait() {
+    let source = Source::read("inputs/day13.sample.txt").unwrap();
+    let input = <day13::Solver as Solution>::parse(&source, &[]).unwrap();
+    assert_eq!(Some("295".to_string()), day13::Solver::part1(&input));
+
+    let run = find_day(13).unwrap().run(&source, &[], &[2]).unwrap();
+    assert_eq!(Some("1068781".to_string()), run.parts[0].answer);
+}
+
+#[test]
+fn literal_input() {
+    assert_eq!(Ok(vec![1721, 979]), day1::parse_ints("1721\n979\n"));
+
+    let source = Source::new("<test>", "1721\n979\n366\n299\n675\n1456\n");
+    let run = find_day(1).unwrap().run(&source, &[], &[2]).unwrap();
+    assert_eq!(Some("241861950".to_string()), run.parts[0].answer);
+
+    let source = Source::from_reader("<test>", "1721\r\nx\r\n".as_bytes()).unwrap();
+    let err = find_day(1).unwrap().run(&source, &[], &[2]).unwrap_err();
+    assert_eq!(("<test>", 2), (err.file.as_str(), err.line));
+}
+
+#[test]
+fn parse_errors() {
+    let err = Source::read("inputs/day8.txt.missing").err().unwrap();
+    assert_eq!(("inputs/day8.txt.missing", 0), (err.file.as_str(), err.line));
+
+    let source = Source::read("inputs/day9.sample.txt").unwrap();
+    let err = find_day(9).unwrap().run(&source, &["x".to_string()], &[1]).unwrap_err();
+    assert_eq!(ParseError::arg("x", "Invalid argument: 'x'"), err);
+}
+
+#[test]
+fn answers_manifest() {
+    let answers = read_manifest("inputs/answers.toml").unwrap();
+    for expected in answers.iter() {
+        assert!(find_day(expected.day).is_some(), "no solution for day {}", expected.day);
+        assert!(std::path::Path::new(&expected.input).exists(), "missing {}", expected.input);
+    }
+}
+
+#[test]
+fn crlf_samples() {
+    // Windows line endings, a byte order mark and extra blank lines at the end shouldn't
+    // change any answers.
+    let dir = std::env::temp_dir().join(format!("aoc2020-crlf-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let answers = read_manifest("inputs/answers.toml").unwrap();
+    // Days 15 and 23 take too long in debug builds.
+    for expected in answers.iter().filter(|e| e.input.contains("sample") && e.day != 15 && e.day != 23) {
+        let text = std::fs::read_to_string(&expected.input).unwrap();
+        let crlf = format!("\u{feff}{}\r\n\r\n \r\n", text.replace('\n', "\r\n"));
+        let path = dir.join(std::path::Path::new(&expected.input).file_name().unwrap());
+        std::fs::write(&path, crlf).unwrap();
+
+        let path = path.to_str().unwrap();
+        let source = Source::read(path).unwrap();
+        let run = find_day(expected.day).unwrap().run(&source, &expected.args, &[1, 2]);
+        let run = run.unwrap_or_else(|e| panic!("{}\n{}", expected.input, e.diagnostic()));
+        for part in run.parts {
+            if let Some(want) = expected.part(part.part) {
+                assert_eq!(Some(want), part.answer.as_ref(), "{} part {}", expected.input, part.part);
+            }
+        }
+    }
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn parallel_searches() {
+    // The searches in days 1, 8 and 20 find the same answers on more than one thread.
+    aoc2020::pool::set_jobs(4);
+    let answers = read_manifest("inputs/answers.toml").unwrap();
+    for expected in answers.iter().filter(|e| [1, 8, 20].contains(&e.day) && e.input.contains("sample")) {
+        let source = Source::read(&expected.input).unwrap();
+        let run = find_day(expected.day).unwrap().run(&source, &expected.args, &[1, 2]).unwrap();
+        for part in run.parts {
+            assert_eq!(expected.part(part.part), part.answer.as_ref(), "{} part {}", expected.input, part.part);
+        }
+    }
+}
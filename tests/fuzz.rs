@@ -0,0 +1,22 @@
+// A short run of each fuzz target, so a parser that starts panicking fails the build.
+// `aoc fuzz` runs them for longer.
+use aoc2020::fuzz::{corpus, fuzz, targets, Unit};
+use aoc2020::util::json_string;
+
+#[test]
+fn parsers_never_panic() {
+    for target in targets() {
+        let corpus = corpus(&target, "inputs").unwrap();
+        assert!(!corpus.is_empty(), "No corpus for {}", target.name);
+        let cases = if target.unit == Unit::File { 50 } else { 2000 };
+        if let Err(crash) = fuzz(&target, &corpus, cases, 0) {
+            panic!(
+                "{} panicked on seed {:?}: {}\ninput: {}",
+                target.name,
+                crash.seed,
+                crash.message,
+                json_string(&crash.input)
+            );
+        }
+    }
+}
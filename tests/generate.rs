@@ -0,0 +1,33 @@
+// Each day's solver against the answers planted in a few small generated inputs.
+// `aoc generate <day> --check` does the same for one input of any size.
+use aoc2020::solution::days;
+use aoc2020::util::Source;
+
+#[test]
+fn generated_inputs_solve() {
+    for day in days() {
+        for seed in 0..3 {
+            let generated = match day.generate(seed, 20) {
+                Some(g) => g,
+                None => continue,
+            };
+            assert_eq!(Some(&generated), day.generate(seed, 20).as_ref(), "Day {} isn't deterministic", day.day);
+
+            // Only solve the parts with known answers, since some of the others (like
+            // day 15's part 2) take a while in debug builds.
+            let parts: Vec<u32> = [(1, &generated.part1), (2, &generated.part2)]
+                .iter()
+                .filter(|(_, answer)| answer.is_some())
+                .map(|&(part, _)| part)
+                .collect();
+            let source = Source::new(&format!("<day {} seed {}>", day.day, seed), &generated.input);
+            let run = day
+                .run(&source, &generated.args, &parts)
+                .unwrap_or_else(|e| panic!("Day {} seed {}:\n{}", day.day, seed, e.diagnostic()));
+            for part in run.parts {
+                let want = if part.part == 1 { &generated.part1 } else { &generated.part2 };
+                assert_eq!(*want, part.answer, "Day {} part {} seed {}", day.day, part.part, seed);
+            }
+        }
+    }
+}